@@ -4,7 +4,7 @@ extern crate piston;
 extern crate graphics;
 extern crate split_controller;
 
-use split_controller::{SplitLayoutController, SplitLayoutSettings, SplitState};
+use split_controller::{PanelId, SplitLayoutController, SplitLayoutSettings, SplitState};
 use sdl2_window::Sdl2Window;
 use opengl_graphics::{GlGraphics, OpenGL};
 use piston::event_loop::{Events, EventSettings};
@@ -20,7 +20,7 @@ fn main() {
         .graphics_api(opengl);
     let mut window: Sdl2Window = settings.build().unwrap();
 
-    let ref mut split_layout_settings = SplitLayoutSettings::new(2.0, 100.0);
+    let split_layout_settings = &mut SplitLayoutSettings::new(2.0, 100.0);
     split_layout_settings.center_min_size = [10.0; 2];
     let mut split_layout = SplitLayoutController::new(split_layout_settings);
     let show_min_size = false;
@@ -32,7 +32,7 @@ fn main() {
         let window_size = window.size();
         let split_layout_bounds = [
             margin, margin,
-            window_size.width as f64 - margin * 2.0, window_size.height as f64 - margin * 2.0
+            window_size.width - margin * 2.0, window_size.height - margin * 2.0
         ];
 
         split_layout.event(split_layout_bounds, math::identity(), &e);
@@ -41,26 +41,29 @@ fn main() {
             gl.draw(args.viewport(), |c, g| {
                 clear([1.0; 4], g);
 
-                let rectangles = split_layout.rectangles(split_layout_bounds);
-                let states = split_layout.states();
-                for i in 0..4 {
-                    let color = match states[i] {
+                for (_, line, state) in split_layout.sides(split_layout_bounds) {
+                    let color = match state {
                         SplitState::Inactive => [0.5, 0.5, 0.5, 1.0],
                         SplitState::Hover => [0.8, 0.8, 0.8, 1.0],
                         SplitState::Drag => [0.6, 0.6, 0.6, 1.0],
                         SplitState::DragNotFollowing => [1.0, 0.8, 0.8, 1.0],
+                        SplitState::CollapsePending => [1.0, 0.6, 0.6, 1.0],
+                        SplitState::Focused => [0.4, 0.4, 0.8, 1.0],
+                        SplitState::CornerDrag => [0.6, 0.6, 0.6, 1.0],
+                        SplitState::Locked => [0.3, 0.3, 0.3, 1.0],
                     };
-                    rectangle(color, rectangles[i], c.transform, g);
+                    rectangle(color, line, c.transform, g);
                 }
 
                 let panels = split_layout.panel_rectangles(split_layout_bounds);
-                for (i, &panel) in panels.iter().enumerate() {
-                    let color = match i {
-                        0 | 1 => [0.9, 0.9, 0.9, 1.0],
-                        2 | 3 => [0.7, 0.7, 0.7, 1.0],
-                        _ => [0.2, 0.2, 0.2, 1.0],
+                for &panel in &[PanelId::Left, PanelId::Right, PanelId::Top,
+                                PanelId::Bottom, PanelId::Center] {
+                    let color = match panel {
+                        PanelId::Left | PanelId::Right => [0.9, 0.9, 0.9, 1.0],
+                        PanelId::Top | PanelId::Bottom => [0.7, 0.7, 0.7, 1.0],
+                        PanelId::Center => [0.2, 0.2, 0.2, 1.0],
                     };
-                    rectangle(color, panel, c.transform, g);
+                    rectangle(color, panels[panel], c.transform, g);
                 }
 
                 if show_min_size {
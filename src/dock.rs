@@ -0,0 +1,437 @@
+//! Docking of panels by dragging their headers.
+
+use std::mem;
+
+use input::{Button, GenericEvent, Key, MouseButton};
+
+use super::math::{is_inside, inside_pos, Matrix2d, Rectangle, Vec2d};
+use super::{FloatingController, FloatingHandle, FloatingPanel};
+use super::{SplitController, SplitNode, SplitOrientation, SplitTree, TabGroup};
+
+/// Where a panel is docked relative to the target panel.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DropZone {
+    /// Docks to the left of target panel.
+    Left,
+    /// Docks to the right of target panel.
+    Right,
+    /// Docks above the target panel.
+    Top,
+    /// Docks below the target panel.
+    Bottom,
+    /// Adds the panel to a tab group with the target panel.
+    Center,
+}
+
+/// Stores information about docking panels in a split tree.
+///
+/// Each panel has a header at the top, which the user can drag onto another panel.
+/// Panels in a tab group are dragged by their tabs instead.
+/// Dropping near an edge of the target panel splits it, while dropping in the center
+/// adds the dragged panel to a tab group with the target panel.
+/// The dragged panel is removed from its old place.
+///
+/// Panels can also be undocked into a floating layer above the tree.
+/// Dragging the title bar of a floating panel near an edge of a docked panel docks it back.
+pub struct DockController {
+    /// The split tree of panels.
+    pub tree: SplitTree,
+    /// The floating panels.
+    pub floating: FloatingController,
+    /// The height of panel headers.
+    pub header_height: f64,
+    /// The border width of new splits.
+    pub border: f64,
+    /// The minimum value of new splits.
+    pub min_value: f64,
+    /// The fraction of target panel size that drops at an edge.
+    pub edge_fraction: f64,
+    // The last position of mouse cursor.
+    cursor: Vec2d,
+    // Which panel is dragged by its header.
+    dragging: Option<usize>,
+    // The panel and zone where the dragged panel is dropped.
+    drop_target: Option<(usize, DropZone)>,
+    // The scale of normalized touch positions for new splits.
+    touch_scale: [f64; 2],
+}
+
+impl DockController {
+    /// Creates a new `DockController`.
+    ///
+    /// New splits get the same minimum value as border width.
+    /// The edge fraction is set to 0.25.
+    /// Floating panels get title bars with the same height as headers.
+    pub fn new(tree: SplitTree, border: f64, header_height: f64) -> DockController {
+        DockController {
+            tree,
+            floating: FloatingController::new(header_height),
+            header_height,
+            border,
+            min_value: border,
+            edge_fraction: 0.25,
+            cursor: [0.0; 2],
+            dragging: None,
+            drop_target: None,
+            touch_scale: [1.0; 2],
+        }
+    }
+
+    /// Gets the panel currently being dragged by the user.
+    pub fn dragging(&self) -> Option<usize> {self.dragging}
+
+    /// Gets the panel and zone where the dragged panel is dropped.
+    pub fn drop_target(&self) -> Option<(usize, DropZone)> {self.drop_target}
+
+    /// Sets the scale of normalized touch positions for all splits, usually the window size.
+    ///
+    /// This is required for touch input until the first resize event.
+    pub fn set_touch_scale(&mut self, touch_scale: [f64; 2]) {
+        self.touch_scale = touch_scale;
+        self.tree.set_touch_scale(touch_scale);
+    }
+
+    /// Handles event.
+    ///
+    /// While a panel is dragged, the splits do not receive events.
+    /// Floating panels receive events before the tree, and cover the panels below them.
+    /// Escape cancels dragging a panel.
+    pub fn event<E: GenericEvent>(&mut self, rect: Rectangle, transform: Matrix2d, e: &E) {
+        if let Some(args) = e.resize_args() {
+            self.set_touch_scale(args.window_size);
+        }
+        let floating_drag = match self.floating.dragging() {
+            Some((id, FloatingHandle::Title)) => Some(id),
+            _ => None,
+        };
+        if self.dragging.is_none() && !self.tree.root.is_dragging() {
+            self.floating.event(rect, transform, e);
+        }
+        let captured = self.floating.hover().is_some() || self.floating.dragging().is_some();
+        // Tab groups keep reordering a dragged tab until it is dropped on another panel.
+        let tab_drag = self.dragging
+            .map(|id| self.tree.tab_rectangles(rect).iter().any(|&(tab, _)| tab == id))
+            .unwrap_or(false);
+        if (self.dragging.is_none() || tab_drag) && (!captured || e.release_args().is_some()) {
+            self.tree.event(rect, transform, e);
+        }
+
+        if let Some(pos) = e.mouse_cursor_args() {
+            self.cursor = inside_pos(pos, transform);
+            self.drop_target = match (self.dragging, floating_drag) {
+                (Some(id), _) => self.find_drop_target(id, false, rect),
+                (None, Some(id)) => self.find_drop_target(id, true, rect),
+                (None, None) => None,
+            };
+        }
+
+        if let Some(Button::Mouse(MouseButton::Left)) = e.press_args() {
+            if !captured && !self.tree.root.is_dragging() {
+                let cursor = self.cursor;
+                self.dragging = self.drag_handles(rect).into_iter()
+                    .find(|&(_, handle)| is_inside(cursor, handle))
+                    .map(|(id, _)| id);
+                self.drop_target = None;
+            }
+        }
+
+        if let Some(Button::Keyboard(Key::Escape)) = e.press_args() {
+            self.dragging = None;
+            self.drop_target = None;
+        }
+
+        if let Some(Button::Mouse(MouseButton::Left)) = e.release_args() {
+            if let (Some(id), Some((target, zone))) = (self.dragging, self.drop_target) {
+                self.dock(id, target, zone, rect);
+            } else if let (Some(id), Some((target, zone))) = (floating_drag, self.drop_target) {
+                self.dock_floating(id, target, zone, rect);
+            }
+            self.dragging = None;
+            self.drop_target = None;
+        }
+    }
+
+    /// Docks a panel relative to a target panel within rectangle `[x, y, w, h]` of tree.
+    ///
+    /// The new split divides the target panel in half.
+    /// When the target panel is in a tab group, the whole group is split.
+    /// Returns `false` if the layout could not be changed.
+    pub fn dock(&mut self, id: usize, target: usize, zone: DropZone, rect: Rectangle) -> bool {
+        if id == target {return false};
+        if self.tree.root.leaf_mut(target).is_none() || !self.tree.root.remove(id) {
+            return false;
+        }
+        self.insert(id, target, zone, rect);
+        true
+    }
+
+    /// Docks a floating panel relative to a target panel within rectangle `[x, y, w, h]` of tree.
+    ///
+    /// Returns `false` if the panel is not floating or the target panel is not found.
+    pub fn dock_floating(
+        &mut self,
+        id: usize,
+        target: usize,
+        zone: DropZone,
+        rect: Rectangle
+    ) -> bool {
+        if self.tree.root.leaf_mut(target).is_none() || self.floating.remove(id).is_none() {
+            return false;
+        }
+        self.insert(id, target, zone, rect);
+        true
+    }
+
+    /// Undocks a panel into a floating panel with rectangle `[x, y, w, h]`.
+    ///
+    /// Returns `false` if the panel is not found or is the only docked panel.
+    pub fn undock(&mut self, id: usize, panel_rect: Rectangle) -> bool {
+        if !self.tree.root.remove(id) {return false};
+        self.floating.add(FloatingPanel::new(id, panel_rect));
+        true
+    }
+
+    /// Inserts a panel that is not in the tree relative to a target panel.
+    fn insert(&mut self, id: usize, target: usize, zone: DropZone, rect: Rectangle) {
+        if zone == DropZone::Center {
+            let node = self.tree.root.leaf_mut(target).unwrap();
+            match *node {
+                SplitNode::Tabs(ref mut group) => group.push(id),
+                _ => {
+                    let mut group = TabGroup::new(vec![target], self.header_height);
+                    group.push(id);
+                    *node = SplitNode::Tabs(group);
+                }
+            }
+            return;
+        }
+
+        let visible = match *self.tree.root.leaf_mut(target).unwrap() {
+            SplitNode::Tabs(ref group) => group.active_panel().unwrap_or(target),
+            _ => target,
+        };
+        let target_rect = self.tree.panel_rectangles(rect).into_iter()
+            .find(|&(panel, _)| panel == visible)
+            .map(|(_, target_rect)| target_rect)
+            .unwrap_or([0.0; 4]);
+        let (orientation, size) = match zone {
+            DropZone::Left => (SplitOrientation::Left, target_rect[2]),
+            DropZone::Right => (SplitOrientation::Right, target_rect[2]),
+            DropZone::Top => (SplitOrientation::Top, target_rect[3]),
+            DropZone::Bottom | DropZone::Center => (SplitOrientation::Bottom, target_rect[3]),
+        };
+        let value = (0.5 * (size - self.border)).max(self.min_value);
+        let mut split = SplitController::new(value, self.min_value, self.border, orientation);
+        split.touch_scale = self.touch_scale;
+        let node = self.tree.root.leaf_mut(target).unwrap();
+        let target_node = mem::replace(node, SplitNode::Leaf(id));
+        *node = match zone {
+            DropZone::Left | DropZone::Top =>
+                SplitNode::split(split, SplitNode::Leaf(id), target_node),
+            _ => SplitNode::split(split, target_node, SplitNode::Leaf(id)),
+        };
+    }
+
+    /// Computes header rectangles of panels, keyed by panel id.
+    ///
+    /// Panels in tab groups have no header, because their tabs are used instead.
+    pub fn header_rectangles(&self, rect: Rectangle) -> Vec<(usize, Rectangle)> {
+        let tabs = self.tree.tab_rectangles(rect);
+        self.tree.panel_rectangles(rect).into_iter()
+            .filter(|&(id, _)| tabs.iter().all(|&(tab, _)| tab != id))
+            .map(|(id, r)| (id, [r[0], r[1], r[2], self.header_height.min(r[3])]))
+            .collect()
+    }
+
+    /// Computes content rectangles of panels below the headers, keyed by panel id.
+    ///
+    /// Panels in tab groups are already below the tab header.
+    pub fn content_rectangles(&self, rect: Rectangle) -> Vec<(usize, Rectangle)> {
+        let tabs = self.tree.tab_rectangles(rect);
+        self.tree.panel_rectangles(rect).into_iter()
+            .map(|(id, r)| {
+                if tabs.iter().any(|&(tab, _)| tab == id) {return (id, r)};
+
+                let header = self.header_height.min(r[3]);
+                (id, [r[0], r[1] + header, r[2], r[3] - header])
+            })
+            .collect()
+    }
+
+    /// Computes the rectangles where panels are dragged from, keyed by panel id.
+    ///
+    /// These are the headers of panels and the tabs of panels in tab groups.
+    fn drag_handles(&self, rect: Rectangle) -> Vec<(usize, Rectangle)> {
+        let mut handles = self.header_rectangles(rect);
+        handles.extend(self.tree.tab_rectangles(rect));
+        handles
+    }
+
+    /// Computes the preview rectangle where the dragged panel is dropped, for drawing.
+    pub fn preview_rect(&self, rect: Rectangle) -> Option<Rectangle> {
+        let (target, zone) = self.drop_target?;
+        let (_, r) = self.tree.panel_rectangles(rect).into_iter()
+            .find(|&(id, _)| id == target)?;
+        Some(match zone {
+            DropZone::Left => [r[0], r[1], 0.5 * r[2], r[3]],
+            DropZone::Right => [r[0] + 0.5 * r[2], r[1], 0.5 * r[2], r[3]],
+            DropZone::Top => [r[0], r[1], r[2], 0.5 * r[3]],
+            DropZone::Bottom => [r[0], r[1] + 0.5 * r[3], r[2], 0.5 * r[3]],
+            DropZone::Center => r,
+        })
+    }
+
+    /// Finds the panel and zone under the mouse cursor while dragging a panel.
+    fn find_drop_target(
+        &self,
+        id: usize,
+        edges_only: bool,
+        rect: Rectangle
+    ) -> Option<(usize, DropZone)> {
+        let pos = self.cursor;
+        let (target, r) = self.tree.panel_rectangles(rect).into_iter()
+            .find(|&(_, r)| is_inside(pos, r))?;
+        if target == id {return None};
+
+        let u = (pos[0] - r[0]) / r[2];
+        let v = (pos[1] - r[1]) / r[3];
+        let edges = [
+            (u, DropZone::Left), (1.0 - u, DropZone::Right),
+            (v, DropZone::Top), (1.0 - v, DropZone::Bottom),
+        ];
+        let (distance, zone) = edges.iter().cloned()
+            .fold((f64::INFINITY, DropZone::Center), |a, b| if b.0 < a.0 {b} else {a});
+        if distance < self.edge_fraction {
+            Some((target, zone))
+        } else if edges_only {
+            None
+        } else {
+            Some((target, DropZone::Center))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::{ButtonArgs, ButtonState, Event, Input, Motion};
+
+    const RECT: Rectangle = [0.0, 0.0, 400.0, 300.0];
+    const IDENTITY: Matrix2d = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+    fn dock() -> DockController {
+        let split = SplitController::new(200.0, 10.0, 2.0, SplitOrientation::Left);
+        let tree = SplitTree::new(SplitNode::split(split, SplitNode::Leaf(1), SplitNode::Leaf(2)));
+        DockController::new(tree, 2.0, 20.0)
+    }
+
+    fn panels(dock: &DockController) -> Vec<(usize, Rectangle)> {
+        dock.tree.panel_rectangles(RECT)
+    }
+
+    fn tab_dock() -> DockController {
+        let split = SplitController::new(200.0, 10.0, 2.0, SplitOrientation::Left);
+        let tabs = SplitNode::Tabs(TabGroup::new(vec![2, 3], 20.0));
+        let tree = SplitTree::new(SplitNode::split(split, SplitNode::Leaf(1), tabs));
+        DockController::new(tree, 2.0, 20.0)
+    }
+
+    fn cursor(pos: Vec2d) -> Event {
+        Event::Input(Input::Move(Motion::MouseCursor(pos)), None)
+    }
+
+    fn button(state: ButtonState) -> Event {
+        let button = Button::Mouse(MouseButton::Left);
+        Event::Input(Input::Button(ButtonArgs {state, button, scancode: None}), None)
+    }
+
+    #[test]
+    fn dock_at_edge_splits_target() {
+        let mut dock = dock();
+        assert!(dock.dock(2, 1, DropZone::Bottom, RECT));
+        assert_eq!(panels(&dock), vec![
+            (1, [0.0, 0.0, 400.0, 149.0]),
+            (2, [0.0, 151.0, 400.0, 149.0]),
+        ]);
+    }
+
+    #[test]
+    fn dock_in_center_adds_tab() {
+        let mut dock = dock();
+        assert!(dock.dock(2, 1, DropZone::Center, RECT));
+        match dock.tree.root {
+            SplitNode::Tabs(ref group) => assert_eq!(group.panels, vec![1, 2]),
+            _ => panic!("expected tab group"),
+        }
+        // Docking a tab at an edge of its own group moves it out of the group.
+        assert!(dock.dock(1, 2, DropZone::Left, RECT));
+        assert_eq!(panels(&dock), vec![
+            (1, [0.0, 0.0, 199.0, 300.0]),
+            (2, [201.0, 0.0, 199.0, 300.0]),
+        ]);
+    }
+
+    #[test]
+    fn dock_to_itself_or_missing_panel() {
+        let mut dock = dock();
+        assert!(!dock.dock(1, 1, DropZone::Left, RECT));
+        assert!(!dock.dock(1, 3, DropZone::Left, RECT));
+        assert!(!dock.dock(3, 1, DropZone::Left, RECT));
+        assert_eq!(panels(&dock).len(), 2);
+    }
+
+    #[test]
+    fn undock_and_dock_floating() {
+        let mut dock = dock();
+        assert!(dock.undock(2, [50.0, 50.0, 100.0, 100.0]));
+        assert!(!dock.undock(1, [50.0, 50.0, 100.0, 100.0]));
+        assert!(dock.tree.root.is_leaf(1));
+        assert!(dock.floating.panel(2).is_some());
+        assert!(dock.dock_floating(2, 1, DropZone::Right, RECT));
+        assert!(dock.floating.panel(2).is_none());
+        assert_eq!(panels(&dock), vec![
+            (1, [0.0, 0.0, 199.0, 300.0]),
+            (2, [201.0, 0.0, 199.0, 300.0]),
+        ]);
+    }
+
+    #[test]
+    fn tab_group_has_no_extra_header() {
+        let dock = tab_dock();
+        assert_eq!(dock.header_rectangles(RECT), vec![(1, [0.0, 0.0, 200.0, 20.0])]);
+        assert_eq!(dock.content_rectangles(RECT), vec![
+            (1, [0.0, 20.0, 200.0, 280.0]),
+            (2, [202.0, 20.0, 198.0, 280.0]),
+        ]);
+    }
+
+    #[test]
+    fn drag_tab_out_of_group() {
+        let mut dock = tab_dock();
+        dock.event(RECT, IDENTITY, &cursor([350.0, 10.0]));
+        dock.event(RECT, IDENTITY, &button(ButtonState::Press));
+        assert_eq!(dock.dragging(), Some(3));
+        dock.event(RECT, IDENTITY, &cursor([20.0, 150.0]));
+        assert_eq!(dock.drop_target(), Some((1, DropZone::Left)));
+        dock.event(RECT, IDENTITY, &button(ButtonState::Release));
+        assert_eq!(dock.dragging(), None);
+        let ids: Vec<usize> = panels(&dock).into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, vec![3, 1, 2]);
+        assert!(dock.tree.tab_rectangles(RECT).is_empty());
+    }
+
+    #[test]
+    fn drag_tab_within_group_reorders() {
+        let mut dock = tab_dock();
+        dock.event(RECT, IDENTITY, &cursor([350.0, 10.0]));
+        dock.event(RECT, IDENTITY, &button(ButtonState::Press));
+        dock.event(RECT, IDENTITY, &cursor([250.0, 10.0]));
+        assert_eq!(dock.drop_target(), None);
+        dock.event(RECT, IDENTITY, &button(ButtonState::Release));
+        let tabs: Vec<usize> = dock.tree.tab_rectangles(RECT).into_iter()
+            .map(|(id, _)| id)
+            .collect();
+        assert_eq!(tabs, vec![3, 2]);
+    }
+}
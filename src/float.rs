@@ -0,0 +1,539 @@
+//! Floating panels above a split layout.
+
+use input::{Button, GenericEvent, Key, MouseButton};
+
+use super::math::{is_inside, inside_pos, margin_rect, Matrix2d, Rectangle, Vec2d};
+use super::{Corner, Side, Sides, SplitLayoutController, SplitLayoutEvent};
+use super::SIDES;
+
+/// Identifies a part of floating panel that the user can drag.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FloatingHandle {
+    /// The title bar, which moves the panel.
+    Title,
+    /// An edge, which resizes the panel.
+    Edge(Side),
+    /// A corner, which resizes the panel at two edges.
+    Corner(Corner),
+}
+
+/// Stores information about a floating panel.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FloatingPanel {
+    /// The panel id.
+    pub id: usize,
+    /// The panel rectangle `[x, y, w, h]`, including the title bar.
+    pub rect: Rectangle,
+    /// The minimum size of panel.
+    pub min_size: [f64; 2],
+}
+
+impl FloatingPanel {
+    /// Creates a new `FloatingPanel` without minimum size.
+    pub fn new(id: usize, rect: Rectangle) -> FloatingPanel {
+        FloatingPanel {
+            id,
+            rect,
+            min_size: [0.0; 2],
+        }
+    }
+
+    /// Sets the minimum size.
+    pub fn min_size(mut self, min_size: [f64; 2]) -> FloatingPanel {
+        self.min_size = min_size;
+        self
+    }
+}
+
+/// Stores information about a layer of floating panels.
+///
+/// Panels are ordered from back to front, and clicking a panel brings it to front.
+/// The title bar moves a panel, while edges and corners resize it.
+pub struct FloatingController {
+    /// The panels, from back to front.
+    pub panels: Vec<FloatingPanel>,
+    /// The height of title bars.
+    pub title_height: f64,
+    /// The margin around edges of panels for resizing.
+    pub hit_margin: f64,
+    // The last position of mouse cursor.
+    cursor: Vec2d,
+    // The panel and handle the mouse is hovering over.
+    mouse_hover: Option<(usize, Option<FloatingHandle>)>,
+    // The panel and handle being dragged.
+    dragging: Option<(usize, FloatingHandle)>,
+    // The panel rectangle and cursor position when the user started dragging.
+    drag_start: (Rectangle, Vec2d),
+}
+
+impl FloatingController {
+    /// Creates a new `FloatingController` without panels.
+    ///
+    /// The margin for resizing is set to 4.
+    pub fn new(title_height: f64) -> FloatingController {
+        FloatingController {
+            panels: vec![],
+            title_height,
+            hit_margin: 4.0,
+            cursor: [0.0; 2],
+            mouse_hover: None,
+            dragging: None,
+            drag_start: ([0.0; 4], [0.0; 2]),
+        }
+    }
+
+    /// Adds a panel in front of the others.
+    pub fn add(&mut self, panel: FloatingPanel) {
+        self.panels.push(panel);
+    }
+
+    /// Removes a panel.
+    pub fn remove(&mut self, id: usize) -> Option<FloatingPanel> {
+        let i = self.panels.iter().position(|panel| panel.id == id)?;
+        if self.mouse_hover.map(|(hover, _)| hover == id).unwrap_or(false) {
+            self.mouse_hover = None;
+        }
+        if self.dragging.map(|(dragging, _)| dragging == id).unwrap_or(false) {
+            self.dragging = None;
+        }
+        Some(self.panels.remove(i))
+    }
+
+    /// Gets a panel.
+    pub fn panel(&self, id: usize) -> Option<&FloatingPanel> {
+        self.panels.iter().find(|panel| panel.id == id)
+    }
+
+    /// Brings a panel in front of the others.
+    pub fn raise(&mut self, id: usize) {
+        if let Some(i) = self.panels.iter().position(|panel| panel.id == id) {
+            let panel = self.panels.remove(i);
+            self.panels.push(panel);
+        }
+    }
+
+    /// Gets the panel and handle the mouse is hovering over.
+    ///
+    /// The handle is `None` when hovering over the content of panel.
+    pub fn hover(&self) -> Option<(usize, Option<FloatingHandle>)> {self.mouse_hover}
+
+    /// Gets the panel and handle currently being dragged by the user.
+    pub fn dragging(&self) -> Option<(usize, FloatingHandle)> {self.dragging}
+
+    /// Handles event.
+    ///
+    /// Panels are kept inside rectangle `[x, y, w, h]` when moved or resized.
+    /// Escape cancels dragging and restores the panel rectangle.
+    pub fn event<E: GenericEvent>(&mut self, rect: Rectangle, transform: Matrix2d, e: &E) {
+        if let Some(pos) = e.mouse_cursor_args() {
+            let pos = inside_pos(pos, transform);
+            self.cursor = pos;
+            if let Some((id, handle)) = self.dragging {
+                self.drag_to(id, handle, pos, rect);
+            }
+            self.mouse_hover = self.panels.iter().rev()
+                .filter_map(|panel| self.hit(panel.rect, pos).map(|handle| (panel.id, handle)))
+                .next();
+        }
+
+        if let Some(Button::Mouse(MouseButton::Left)) = e.press_args() {
+            if let Some((id, handle)) = self.mouse_hover {
+                self.raise(id);
+                if let Some(handle) = handle {
+                    self.dragging = Some((id, handle));
+                    self.drag_start = (self.panel(id).unwrap().rect, self.cursor);
+                }
+            }
+        }
+
+        if let Some(Button::Keyboard(Key::Escape)) = e.press_args() {
+            if let Some((id, _)) = self.dragging {
+                let start = self.drag_start.0;
+                if let Some(panel) = self.panels.iter_mut().find(|panel| panel.id == id) {
+                    panel.rect = start;
+                }
+                self.dragging = None;
+            }
+        }
+
+        if let Some(Button::Mouse(MouseButton::Left)) = e.release_args() {
+            self.dragging = None;
+        }
+    }
+
+    /// Computes panel rectangles for drawing from back to front, keyed by panel id.
+    pub fn panel_rectangles(&self) -> Vec<(usize, Rectangle)> {
+        self.panels.iter().map(|panel| (panel.id, panel.rect)).collect()
+    }
+
+    /// Computes title bar rectangles from back to front, keyed by panel id.
+    pub fn title_rectangles(&self) -> Vec<(usize, Rectangle)> {
+        self.panels.iter().map(|panel| (panel.id, self.title_rect(panel.rect))).collect()
+    }
+
+    /// Computes content rectangles below the title bars from back to front, keyed by panel id.
+    pub fn content_rectangles(&self) -> Vec<(usize, Rectangle)> {
+        self.panels.iter().map(|panel| {
+            let r = panel.rect;
+            let title = self.title_height.min(r[3]);
+            (panel.id, [r[0], r[1] + title, r[2], r[3] - title])
+        }).collect()
+    }
+
+    /// Gets the title bar rectangle of a panel.
+    fn title_rect(&self, r: Rectangle) -> Rectangle {
+        [r[0], r[1], r[2], self.title_height.min(r[3])]
+    }
+
+    /// Finds the handle at a position, `Some(None)` when inside the panel but not on a handle.
+    ///
+    /// Edges are hit tested like splits, as lines expanded by the hit margin.
+    fn hit(&self, r: Rectangle, pos: Vec2d) -> Option<Option<FloatingHandle>> {
+        let m = self.hit_margin;
+        let (right, bottom) = (r[0] + r[2], r[1] + r[3]);
+        let corners = [
+            (Corner::TopLeft, [r[0], r[1]]), (Corner::TopRight, [right, r[1]]),
+            (Corner::BottomLeft, [r[0], bottom]), (Corner::BottomRight, [right, bottom]),
+        ];
+        for &(corner, [x, y]) in &corners {
+            if is_inside(pos, margin_rect([x, y, 0.0, 0.0], [m, m])) {
+                return Some(Some(FloatingHandle::Corner(corner)));
+            }
+        }
+        let edges = [
+            (Side::Left, margin_rect([r[0], r[1], 0.0, r[3]], [m, 0.0])),
+            (Side::Right, margin_rect([right, r[1], 0.0, r[3]], [m, 0.0])),
+            (Side::Top, margin_rect([r[0], r[1], r[2], 0.0], [0.0, m])),
+            (Side::Bottom, margin_rect([r[0], bottom, r[2], 0.0], [0.0, m])),
+        ];
+        for &(side, edge) in &edges {
+            if is_inside(pos, edge) {
+                return Some(Some(FloatingHandle::Edge(side)));
+            }
+        }
+        if is_inside(pos, self.title_rect(r)) {
+            Some(Some(FloatingHandle::Title))
+        } else if is_inside(pos, r) {
+            Some(None)
+        } else {
+            None
+        }
+    }
+
+    /// Moves or resizes a panel to the position of mouse cursor, within bounds.
+    fn drag_to(&mut self, id: usize, handle: FloatingHandle, pos: Vec2d, bounds: Rectangle) {
+        let (s, start) = self.drag_start;
+        let (dx, dy) = (pos[0] - start[0], pos[1] - start[1]);
+        let title_height = self.title_height;
+        let panel = match self.panels.iter_mut().find(|panel| panel.id == id) {
+            Some(panel) => panel,
+            None => return,
+        };
+        let min_w = panel.min_size[0];
+        let min_h = panel.min_size[1].max(title_height);
+        let mut r = s;
+        let sides = match handle {
+            FloatingHandle::Title => {
+                r[0] = (s[0] + dx).min(bounds[0] + bounds[2] - s[2]).max(bounds[0]);
+                r[1] = (s[1] + dy).min(bounds[1] + bounds[3] - s[3]).max(bounds[1]);
+                vec![]
+            }
+            FloatingHandle::Edge(side) => vec![side],
+            FloatingHandle::Corner(corner) => corner.splits().to_vec(),
+        };
+        for side in sides {
+            match side {
+                Side::Left => {
+                    r[2] = (s[2] - dx).min(s[0] + s[2] - bounds[0]).max(min_w);
+                    r[0] = s[0] + s[2] - r[2];
+                }
+                Side::Right => {
+                    r[2] = (s[2] + dx).min(bounds[0] + bounds[2] - s[0]).max(min_w);
+                }
+                Side::Top => {
+                    r[3] = (s[3] - dy).min(s[1] + s[3] - bounds[1]).max(min_h);
+                    r[1] = s[1] + s[3] - r[3];
+                }
+                Side::Bottom => {
+                    r[3] = (s[3] + dy).min(bounds[1] + bounds[3] - s[1]).max(min_h);
+                }
+            }
+        }
+        panel.rect = r;
+    }
+}
+
+/// Stores information about a split layout with a layer of floating panels above it.
+///
+/// Each side of the layout holds a panel id.
+/// Undocking a side panel moves it into a floating panel, collapses the split and locks it,
+/// such that the empty side can not be dragged open.
+/// Dragging the title bar of a floating panel near an edge of the layout docks it back
+/// into that side, when the side is empty, and expands the split.
+pub struct FloatingLayout {
+    /// The split layout.
+    pub layout: SplitLayoutController,
+    /// The floating panels.
+    pub floating: FloatingController,
+    /// The distance from an edge of layout where a dragged panel is docked.
+    pub dock_margin: f64,
+    // The panel id docked at each side.
+    docked: Sides<Option<usize>>,
+    // The side where the dragged panel is docked.
+    dock_target: Option<Side>,
+    // Whether each side was locked before its panel was undocked.
+    was_locked: Sides<bool>,
+}
+
+impl FloatingLayout {
+    /// Creates a new `FloatingLayout` with panel ids docked at sides `[left, right, top, bottom]`.
+    ///
+    /// Floating panels get title bars with the given height.
+    /// The dock margin is set to 32.
+    pub fn new(
+        layout: SplitLayoutController,
+        docked: [usize; 4],
+        title_height: f64
+    ) -> FloatingLayout {
+        FloatingLayout {
+            layout,
+            floating: FloatingController::new(title_height),
+            dock_margin: 32.0,
+            docked: Sides([Some(docked[0]), Some(docked[1]), Some(docked[2]), Some(docked[3])]),
+            dock_target: None,
+            was_locked: Sides([false; 4]),
+        }
+    }
+
+    /// Gets the panel id docked at a side, or `None` when the side is empty.
+    pub fn docked(&self, side: Side) -> Option<usize> {self.docked[side]}
+
+    /// Gets the side where the dragged panel is docked when released.
+    pub fn dock_target(&self) -> Option<Side> {self.dock_target}
+
+    /// Undocks the panel at a side into a floating panel with rectangle `[x, y, w, h]`.
+    ///
+    /// The split is locked until a panel is docked into the side.
+    /// Returns `false` if the side is empty.
+    pub fn undock(&mut self, side: Side, panel_rect: Rectangle) -> bool {
+        let id = match self.docked[side].take() {
+            Some(id) => id,
+            None => return false,
+        };
+        self.was_locked[side] = self.layout.is_locked(side);
+        self.layout.set_locked(side, true);
+        self.layout.split_mut(side).collapse();
+        self.floating.add(FloatingPanel::new(id, panel_rect));
+        true
+    }
+
+    /// Docks a floating panel into an empty side.
+    ///
+    /// The split is unlocked, unless it was locked before undocking.
+    /// Returns `false` if the side is not empty or the panel is not floating.
+    pub fn dock(&mut self, id: usize, side: Side) -> bool {
+        if self.docked[side].is_some() || self.floating.remove(id).is_none() {return false};
+        self.docked[side] = Some(id);
+        self.layout.set_locked(side, self.was_locked[side]);
+        self.layout.split_mut(side).expand();
+        true
+    }
+
+    /// Handles event.
+    ///
+    /// Floating panels receive events before the layout, and cover the layout below them.
+    /// While a split is dragged, the floating panels do not receive events.
+    /// Returns notifications about what changed in the layout from its own event handling.
+    pub fn event<E: GenericEvent>(
+        &mut self,
+        rect: Rectangle,
+        transform: Matrix2d,
+        e: &E
+    ) -> Vec<SplitLayoutEvent> {
+        let title_drag = match self.floating.dragging() {
+            Some((id, FloatingHandle::Title)) => Some(id),
+            _ => None,
+        };
+        let layout_dragging = SIDES.iter().any(|&side| self.layout.split(side).is_dragging());
+        if !layout_dragging {
+            self.floating.event(rect, transform, e);
+        }
+        let captured = self.floating.hover().is_some() || self.floating.dragging().is_some();
+        let events = if !captured || e.release_args().is_some() {
+            self.layout.event(rect, transform, e)
+        } else {
+            vec![]
+        };
+
+        if let Some(pos) = e.mouse_cursor_args() {
+            let pos = inside_pos(pos, transform);
+            self.dock_target = title_drag.and_then(|_| self.find_dock_target(pos, rect));
+        }
+
+        if let Some(Button::Keyboard(Key::Escape)) = e.press_args() {
+            self.dock_target = None;
+        }
+
+        if let Some(Button::Mouse(MouseButton::Left)) = e.release_args() {
+            if let (Some(id), Some(side)) = (title_drag, self.dock_target) {
+                self.dock(id, side);
+            }
+            self.dock_target = None;
+        }
+        events
+    }
+
+    /// Computes the preview rectangle where the dragged panel is docked, for drawing.
+    pub fn preview_rect(&self, rect: Rectangle) -> Option<Rectangle> {
+        let side = self.dock_target?;
+        let b = self.layout.bounds(rect);
+        let value = self.layout.split(side).value;
+        Some(match side {
+            Side::Left => [b[0], b[1], value, b[3]],
+            Side::Right => [b[0] + b[2] - value, b[1], value, b[3]],
+            Side::Top => [b[0], b[1], b[2], value],
+            Side::Bottom => [b[0], b[1] + b[3] - value, b[2], value],
+        })
+    }
+
+    /// Finds the nearest empty side within the dock margin of a position.
+    fn find_dock_target(&self, pos: Vec2d, rect: Rectangle) -> Option<Side> {
+        if !is_inside(pos, rect) {return None};
+
+        let distances = [
+            (pos[0] - rect[0], Side::Left), (rect[0] + rect[2] - pos[0], Side::Right),
+            (pos[1] - rect[1], Side::Top), (rect[1] + rect[3] - pos[1], Side::Bottom),
+        ];
+        distances.iter().cloned()
+            .filter(|&(distance, side)| distance < self.dock_margin && self.docked[side].is_none())
+            .fold(None, |a: Option<(f64, Side)>, b| match a {
+                Some(a) if a.0 <= b.0 => Some(a),
+                _ => Some(b),
+            })
+            .map(|(_, side)| side)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::{ButtonArgs, ButtonState, Event, Input, Motion};
+    use super::super::SplitLayoutSettings;
+
+    const RECT: Rectangle = [0.0, 0.0, 400.0, 300.0];
+    const IDENTITY: Matrix2d = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+    fn cursor(pos: Vec2d) -> Event {
+        Event::Input(Input::Move(Motion::MouseCursor(pos)), None)
+    }
+
+    fn mouse(state: ButtonState) -> Event {
+        let button = Button::Mouse(MouseButton::Left);
+        Event::Input(Input::Button(ButtonArgs {state, button, scancode: None}), None)
+    }
+
+    fn drag(floating: &mut FloatingController, from: Vec2d, to: Vec2d) {
+        floating.event(RECT, IDENTITY, &cursor(from));
+        floating.event(RECT, IDENTITY, &mouse(ButtonState::Press));
+        floating.event(RECT, IDENTITY, &cursor(to));
+        floating.event(RECT, IDENTITY, &mouse(ButtonState::Release));
+    }
+
+    fn controller() -> FloatingController {
+        let mut floating = FloatingController::new(20.0);
+        floating.add(FloatingPanel::new(1, [100.0, 100.0, 100.0, 100.0]).min_size([50.0, 50.0]));
+        floating
+    }
+
+    #[test]
+    fn hit_handles() {
+        let floating = controller();
+        let r = floating.panel(1).unwrap().rect;
+        assert_eq!(floating.hit(r, [97.0, 97.0]),
+                   Some(Some(FloatingHandle::Corner(Corner::TopLeft))));
+        assert_eq!(floating.hit(r, [203.0, 150.0]),
+                   Some(Some(FloatingHandle::Edge(Side::Right))));
+        assert_eq!(floating.hit(r, [150.0, 110.0]), Some(Some(FloatingHandle::Title)));
+        assert_eq!(floating.hit(r, [150.0, 150.0]), Some(None));
+        assert_eq!(floating.hit(r, [50.0, 50.0]), None);
+    }
+
+    #[test]
+    fn move_is_clamped_to_bounds() {
+        let mut floating = controller();
+        drag(&mut floating, [150.0, 110.0], [450.0, 410.0]);
+        assert_eq!(floating.panel(1).unwrap().rect, [300.0, 200.0, 100.0, 100.0]);
+    }
+
+    #[test]
+    fn resize_is_clamped_to_bounds() {
+        let mut floating = controller();
+        drag(&mut floating, [200.0, 150.0], [500.0, 150.0]);
+        assert_eq!(floating.panel(1).unwrap().rect, [100.0, 100.0, 300.0, 100.0]);
+        drag(&mut floating, [100.0, 150.0], [-100.0, 150.0]);
+        assert_eq!(floating.panel(1).unwrap().rect, [0.0, 100.0, 400.0, 100.0]);
+        drag(&mut floating, [200.0, 200.0], [200.0, 100.0]);
+        assert_eq!(floating.panel(1).unwrap().rect, [0.0, 100.0, 400.0, 50.0]);
+    }
+
+    #[test]
+    fn undock_and_dock_back() {
+        let settings = SplitLayoutSettings::new(4.0, 10.0).left(100.0, 50.0);
+        let layout = SplitLayoutController::new(&settings);
+        let mut floating = FloatingLayout::new(layout, [1, 2, 3, 4], 20.0);
+        assert!(floating.undock(Side::Left, [200.0, 150.0, 100.0, 100.0]));
+        assert!(!floating.undock(Side::Left, [200.0, 150.0, 100.0, 100.0]));
+        assert_eq!(floating.docked(Side::Left), None);
+        assert!(floating.layout.split(Side::Left).is_collapsed());
+
+        floating.event(RECT, IDENTITY, &cursor([250.0, 160.0]));
+        floating.event(RECT, IDENTITY, &mouse(ButtonState::Press));
+        floating.event(RECT, IDENTITY, &cursor([10.0, 150.0]));
+        assert_eq!(floating.dock_target(), Some(Side::Left));
+        assert_eq!(floating.preview_rect(RECT), Some([0.0, 0.0, 100.0, 300.0]));
+        floating.event(RECT, IDENTITY, &mouse(ButtonState::Release));
+        assert_eq!(floating.docked(Side::Left), Some(1));
+        assert!(!floating.layout.split(Side::Left).is_collapsed());
+        assert!(floating.floating.panels.is_empty());
+    }
+
+    #[test]
+    fn dock_only_into_empty_side() {
+        let settings = SplitLayoutSettings::new(4.0, 10.0);
+        let layout = SplitLayoutController::new(&settings);
+        let mut floating = FloatingLayout::new(layout, [1, 2, 3, 4], 20.0);
+        floating.floating.add(FloatingPanel::new(5, [200.0, 150.0, 100.0, 100.0]));
+        assert!(!floating.dock(5, Side::Right));
+        assert!(floating.undock(Side::Right, [0.0, 0.0, 100.0, 100.0]));
+        assert!(floating.dock(5, Side::Right));
+        assert_eq!(floating.docked(Side::Right), Some(5));
+        assert!(!floating.dock(2, Side::Top));
+    }
+
+    #[test]
+    fn empty_side_can_not_be_dragged_open() {
+        let settings = SplitLayoutSettings::new(4.0, 10.0)
+            .left(100.0, 50.0)
+            .collapse_threshold(20.0)
+            .hit_margin(4.0);
+        let layout = SplitLayoutController::new(&settings);
+        let mut floating = FloatingLayout::new(layout, [1, 2, 3, 4], 20.0);
+        floating.undock(Side::Left, [200.0, 150.0, 100.0, 100.0]);
+        let mut events = vec![];
+        events.extend(floating.event(RECT, IDENTITY, &cursor([1.0, 150.0])));
+        events.extend(floating.event(RECT, IDENTITY, &mouse(ButtonState::Press)));
+        events.extend(floating.event(RECT, IDENTITY, &cursor([100.0, 150.0])));
+        events.extend(floating.event(RECT, IDENTITY, &mouse(ButtonState::Release)));
+        assert!(floating.layout.split(Side::Left).is_collapsed());
+        assert_eq!(events, vec![]);
+
+        floating.layout.set_locked(Side::Right, true);
+        floating.undock(Side::Right, [200.0, 150.0, 100.0, 100.0]);
+        assert!(floating.dock(1, Side::Left));
+        assert!(floating.dock(2, Side::Right));
+        assert!(!floating.layout.is_locked(Side::Left));
+        assert!(floating.layout.is_locked(Side::Right));
+    }
+}
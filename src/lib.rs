@@ -2,20 +2,54 @@
 
 extern crate input;
 extern crate vecmath;
+#[cfg(feature = "serde")]
+extern crate serde;
 
-use input::{Button, GenericEvent, MouseButton};
+use std::mem;
+use std::ops::{Deref, DerefMut, Index, IndexMut};
 
-use self::math::{is_inside, inside_pos, Matrix2d, Rectangle};
+use input::{Button, GenericEvent, Key, MouseButton, Touch};
+use input::keyboard::ModifierKey;
 
+use self::math::{is_inside, inside_pos, margin_rect, Matrix2d, Rectangle, Vec2d};
+
+pub use self::dock::{DockController, DropZone};
+pub use self::float::{FloatingController, FloatingHandle, FloatingLayout, FloatingPanel};
+pub use self::multi::{MultiSplitController, MultiSplitPane, SplitAxis};
+pub use self::tabs::TabGroup;
+pub use self::tree::{SplitNode, SplitTree};
+
+mod dock;
+mod float;
 mod math;
+mod multi;
+mod tabs;
+mod tree;
 
 const LEFT: u8 = 0x1;
 const RIGHT: u8 = 0x2;
 const TOP: u8 = 0x4;
 const BOTTOM: u8 = 0x8;
 
+/// The sides of split layout in the order `[left, right, top, bottom]`.
+const SIDES: [Side; 4] = [
+    Side::Left, Side::Right,
+    Side::Top, Side::Bottom
+];
+
+/// Gets the bit flag of split.
+fn flag(side: Side) -> u8 {
+    match side {
+        Side::Left => LEFT,
+        Side::Right => RIGHT,
+        Side::Top => TOP,
+        Side::Bottom => BOTTOM,
+    }
+}
+
 /// Stores split layout settings.
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SplitLayoutSettings {
     /// The border width.
     pub border: f64,
@@ -25,18 +59,42 @@ pub struct SplitLayoutSettings {
     pub left_value: f64,
     /// The minimum value for left split.
     pub left_min_value: f64,
+    /// The maximum value for left split.
+    pub left_max_value: f64,
+    /// The sizing of left split.
+    pub left_sizing: SplitSizing,
+    /// The grid step of left split values.
+    pub left_step: Option<f64>,
     /// The initial value for right split.
     pub right_value: f64,
     /// The minimum value for right split.
     pub right_min_value: f64,
+    /// The maximum value for right split.
+    pub right_max_value: f64,
+    /// The sizing of right split.
+    pub right_sizing: SplitSizing,
+    /// The grid step of right split values.
+    pub right_step: Option<f64>,
     /// The initial value for top split.
     pub top_value: f64,
     /// The minimum value for top split.
     pub top_min_value: f64,
+    /// The maximum value for top split.
+    pub top_max_value: f64,
+    /// The sizing of top split.
+    pub top_sizing: SplitSizing,
+    /// The grid step of top split values.
+    pub top_step: Option<f64>,
     /// The initial value for bottom split.
     pub bottom_value: f64,
     /// The minimum value for bottom split.
     pub bottom_min_value: f64,
+    /// The maximum value for bottom split.
+    pub bottom_max_value: f64,
+    /// The sizing of bottom split.
+    pub bottom_sizing: SplitSizing,
+    /// The grid step of bottom split values.
+    pub bottom_step: Option<f64>,
     /// Locks left split.
     pub lock_left: bool,
     /// Locks right split.
@@ -45,28 +103,92 @@ pub struct SplitLayoutSettings {
     pub lock_top: bool,
     /// Locks bottom split.
     pub lock_bottom: bool,
+    /// The distance beyond the minimum value for collapsing a split when dragging.
+    ///
+    /// Splits can not be collapsed by dragging when this is `None`.
+    pub collapse_threshold: Option<f64>,
+    /// Decides how splits shrink when the layout gets smaller than the minimum size.
+    pub shrink_policy: ShrinkPolicy,
+    /// The distance to move the focused split with arrow keys.
+    pub keyboard_step: f64,
+    /// The distance to move the focused split with arrow keys while holding Shift.
+    pub keyboard_large_step: f64,
+    /// Whether Tab and Shift-Tab moves keyboard focus between splits.
+    ///
+    /// Arrow keys, Home and End are only used while a split has keyboard focus.
+    pub keyboard_focus: bool,
+    /// Which panels extend into corners `[top_left, top_right, bottom_left, bottom_right]`.
+    pub corner_owners: [CornerOwner; 4],
+    /// The margin around splits for mouse input.
+    pub hit_margin: f64,
+    /// The margin around splits for touch input.
+    pub touch_margin: f64,
+    /// The button that cancels dragging, in addition to Escape.
+    pub cancel_button: Option<Button>,
+    /// The distance the cursor must move after pressing before dragging starts.
+    pub drag_threshold: f64,
+    /// The action when double clicking a split.
+    pub double_click: Option<DoubleClickAction>,
+    /// The maximum time in seconds between two clicks of a double click.
+    pub double_click_time: f64,
+    /// The distance within which dragged splits snap to their snap points.
+    pub snap_radius: f64,
 }
 
 impl SplitLayoutSettings {
     /// Creates a new `SplitLayoutSettings` object with values set to minimum.
     ///
     /// Work area minimum size is set to 1x1.
+    /// There is no maximum value for splits, and splits use absolute sizing.
+    /// The layout overflows when it gets smaller than the minimum size.
+    /// Tab moves keyboard focus between splits.
+    /// Arrow keys move the focused split by 1, or by 10 while holding Shift.
+    /// The top and bottom panels span the full width.
+    /// The mouse margin is set to 0 and the touch margin is set to 8.
+    /// Dragging starts immediately when pressing a split.
+    /// Double clicking a split does nothing, and the double click time is 0.5 seconds.
+    /// The snap radius is set to 8, and split values are not quantized to a grid step.
     pub fn new(border: f64, min_value: f64) -> SplitLayoutSettings {
         SplitLayoutSettings {
-            border: border,
+            border,
             center_min_size: [1.0; 2],
             left_value: min_value,
             left_min_value: min_value,
+            left_max_value: f64::INFINITY,
+            left_sizing: SplitSizing::Absolute,
+            left_step: None,
             right_value: min_value,
             right_min_value: min_value,
+            right_max_value: f64::INFINITY,
+            right_sizing: SplitSizing::Absolute,
+            right_step: None,
             top_value: min_value,
             top_min_value: min_value,
+            top_max_value: f64::INFINITY,
+            top_sizing: SplitSizing::Absolute,
+            top_step: None,
             bottom_value: min_value,
             bottom_min_value: min_value,
+            bottom_max_value: f64::INFINITY,
+            bottom_sizing: SplitSizing::Absolute,
+            bottom_step: None,
             lock_left: false,
             lock_right: false,
             lock_top: false,
             lock_bottom: false,
+            collapse_threshold: None,
+            shrink_policy: ShrinkPolicy::Overflow,
+            keyboard_step: 1.0,
+            keyboard_large_step: 10.0,
+            keyboard_focus: true,
+            corner_owners: [CornerOwner::TopBottom; 4],
+            hit_margin: 0.0,
+            touch_margin: 8.0,
+            cancel_button: None,
+            drag_threshold: 0.0,
+            double_click: None,
+            double_click_time: 0.5,
+            snap_radius: 8.0,
         }
     }
 
@@ -98,6 +220,154 @@ impl SplitLayoutSettings {
         self
     }
 
+    /// Sets the maximum value for left split.
+    pub fn left_max(mut self, max_value: f64) -> SplitLayoutSettings {
+        self.left_max_value = max_value;
+        self
+    }
+
+    /// Sets the maximum value for right split.
+    pub fn right_max(mut self, max_value: f64) -> SplitLayoutSettings {
+        self.right_max_value = max_value;
+        self
+    }
+
+    /// Sets the maximum value for top split.
+    pub fn top_max(mut self, max_value: f64) -> SplitLayoutSettings {
+        self.top_max_value = max_value;
+        self
+    }
+
+    /// Sets the maximum value for bottom split.
+    pub fn bottom_max(mut self, max_value: f64) -> SplitLayoutSettings {
+        self.bottom_max_value = max_value;
+        self
+    }
+
+    /// Sizes left split by a fraction of the width of bounds.
+    pub fn left_fraction(mut self, fraction: f64) -> SplitLayoutSettings {
+        self.left_sizing = SplitSizing::Fraction(fraction);
+        self
+    }
+
+    /// Sizes right split by a fraction of the width of bounds.
+    pub fn right_fraction(mut self, fraction: f64) -> SplitLayoutSettings {
+        self.right_sizing = SplitSizing::Fraction(fraction);
+        self
+    }
+
+    /// Sizes top split by a fraction of the height of bounds.
+    pub fn top_fraction(mut self, fraction: f64) -> SplitLayoutSettings {
+        self.top_sizing = SplitSizing::Fraction(fraction);
+        self
+    }
+
+    /// Sizes bottom split by a fraction of the height of bounds.
+    pub fn bottom_fraction(mut self, fraction: f64) -> SplitLayoutSettings {
+        self.bottom_sizing = SplitSizing::Fraction(fraction);
+        self
+    }
+
+    /// Sets the grid step for left split values.
+    pub fn left_step(mut self, step: f64) -> SplitLayoutSettings {
+        self.left_step = Some(step);
+        self
+    }
+
+    /// Sets the grid step for right split values.
+    pub fn right_step(mut self, step: f64) -> SplitLayoutSettings {
+        self.right_step = Some(step);
+        self
+    }
+
+    /// Sets the grid step for top split values.
+    pub fn top_step(mut self, step: f64) -> SplitLayoutSettings {
+        self.top_step = Some(step);
+        self
+    }
+
+    /// Sets the grid step for bottom split values.
+    pub fn bottom_step(mut self, step: f64) -> SplitLayoutSettings {
+        self.bottom_step = Some(step);
+        self
+    }
+
+    /// Sets the shrink policy.
+    pub fn shrink_policy(mut self, shrink_policy: ShrinkPolicy) -> SplitLayoutSettings {
+        self.shrink_policy = shrink_policy;
+        self
+    }
+
+    /// Sets the distances to move the focused split with arrow keys,
+    /// without and with holding Shift.
+    pub fn keyboard_step(mut self, step: f64, large_step: f64) -> SplitLayoutSettings {
+        self.keyboard_step = step;
+        self.keyboard_large_step = large_step;
+        self
+    }
+
+    /// Sets whether Tab and Shift-Tab moves keyboard focus between splits.
+    pub fn keyboard_focus(mut self, enabled: bool) -> SplitLayoutSettings {
+        self.keyboard_focus = enabled;
+        self
+    }
+
+    /// Sets which panel extends into a corner.
+    pub fn corner_owner(mut self, corner: Corner, owner: CornerOwner) -> SplitLayoutSettings {
+        self.corner_owners[corner as usize] = owner;
+        self
+    }
+
+    /// Sets the margin around splits for mouse input.
+    pub fn hit_margin(mut self, hit_margin: f64) -> SplitLayoutSettings {
+        self.hit_margin = hit_margin;
+        self
+    }
+
+    /// Sets the margin around splits for touch input.
+    pub fn touch_margin(mut self, touch_margin: f64) -> SplitLayoutSettings {
+        self.touch_margin = touch_margin;
+        self
+    }
+
+    /// Sets the button that cancels dragging, in addition to Escape.
+    pub fn cancel_button(mut self, button: Button) -> SplitLayoutSettings {
+        self.cancel_button = Some(button);
+        self
+    }
+
+    /// Sets the distance the cursor must move after pressing before dragging starts.
+    pub fn drag_threshold(mut self, drag_threshold: f64) -> SplitLayoutSettings {
+        self.drag_threshold = drag_threshold;
+        self
+    }
+
+    /// Sets the action when double clicking a split.
+    pub fn double_click(mut self, action: DoubleClickAction) -> SplitLayoutSettings {
+        self.double_click = Some(action);
+        self
+    }
+
+    /// Sets the maximum time in seconds between two clicks of a double click.
+    pub fn double_click_time(mut self, time: f64) -> SplitLayoutSettings {
+        self.double_click_time = time;
+        self
+    }
+
+    /// Sets the distance within which dragged splits snap to their snap points.
+    ///
+    /// Snap points are set on each split.
+    pub fn snap_radius(mut self, snap_radius: f64) -> SplitLayoutSettings {
+        self.snap_radius = snap_radius;
+        self
+    }
+
+    /// Sets the distance beyond the minimum value for collapsing a split when dragging.
+    pub fn collapse_threshold(mut self, threshold: f64) -> SplitLayoutSettings {
+        self.collapse_threshold = Some(threshold);
+        self
+    }
+
     /// Locks left split, sets the minimum value at the same time.
     pub fn lock_left(mut self, value: f64) -> SplitLayoutSettings {
         self.lock_left = true;
@@ -131,6 +401,89 @@ impl SplitLayoutSettings {
     }
 }
 
+/// Notifies about a change in split layout.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum SplitLayoutEvent {
+    /// The user started dragging a split.
+    DragStarted(Side),
+    /// The value of a split changed.
+    ValueChanged {
+        /// The side of split.
+        side: Side,
+        /// The old value.
+        old: f64,
+        /// The new value.
+        new: f64,
+    },
+    /// A split was collapsed or expanded.
+    CollapseChanged {
+        /// The side of split.
+        side: Side,
+        /// Whether the split is collapsed.
+        collapsed: bool,
+    },
+    /// The user stopped dragging a split.
+    DragEnded(Side),
+    /// The mouse started or stopped hovering over a split.
+    HoverChanged {
+        /// The side of split.
+        side: Side,
+        /// Whether the mouse is hovering over the split.
+        hover: bool,
+    },
+    /// The keyboard focus moved to another split or was removed.
+    FocusChanged(Option<Side>),
+}
+
+/// Stores split state for detecting changes.
+struct SplitEventState {
+    value: f64,
+    dragging: bool,
+    hover: bool,
+    collapsed: bool,
+}
+
+impl SplitEventState {
+    fn new(split: &SplitController) -> SplitEventState {
+        SplitEventState {
+            value: split.value,
+            dragging: split.dragging,
+            hover: split.mouse_hover,
+            collapsed: split.collapsed,
+        }
+    }
+}
+
+/// Stores the state of a split that is kept between sessions.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplitSnapshot {
+    /// The value of split.
+    pub value: f64,
+    /// The sizing of split.
+    pub sizing: SplitSizing,
+    /// Whether the split is locked.
+    pub locked: bool,
+    /// Whether the split is collapsed.
+    pub collapsed: bool,
+}
+
+/// Stores the state of split layout that is kept between sessions.
+///
+/// This does not include transient state such as hovering or dragging.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SplitLayoutSnapshot {
+    /// The left split.
+    pub left: SplitSnapshot,
+    /// The right split.
+    pub right: SplitSnapshot,
+    /// The top split.
+    pub top: SplitSnapshot,
+    /// The bottom split.
+    pub bottom: SplitSnapshot,
+}
+
 /// Stores information about split layout.
 ///
 /// The layout is split into left, right, top and bottom panel.
@@ -145,67 +498,182 @@ pub struct SplitLayoutController {
     pub bottom: SplitController,
     // Center minimum size.
     center_min_size: [f64; 2],
+    // Decides how splits shrink.
+    shrink_policy: ShrinkPolicy,
+    // Which panels extend into corners.
+    corner_owners: [CornerOwner; 4],
+    // The distances to move the focused split with arrow keys.
+    keyboard_step: [f64; 2],
+    // Whether Tab moves keyboard focus.
+    keyboard_focus: bool,
+    // Which split has keyboard focus.
+    focus: Option<Side>,
+    // The time in seconds, from update events.
+    time: f64,
+    // The modifier keys being held down.
+    modifiers: ModifierKey,
+    // The last position of mouse cursor.
+    cursor: Vec2d,
+    // Which corner the mouse is hovering over.
+    corner_hover: Option<Corner>,
+    // Which corner is dragged.
+    corner_drag: Option<Corner>,
     // Which splits are dragged.
     drag_splits: u8,
     // Which splits are locked.
     lock_splits: u8,
+    // Notifications about changes outside event handling, returned by the next event.
+    pending_events: Vec<SplitLayoutEvent>,
 }
 
 impl SplitLayoutController {
     /// Creates a new `SplitLayoutController`.
     pub fn new(settings: &SplitLayoutSettings) -> SplitLayoutController {
+        let split = |value: f64, min_value, max_value, sizing, step, orientation| {
+            let mut split = SplitController::new(value, min_value, settings.border, orientation);
+            split.max_value = max_value;
+            split.sizing = sizing;
+            split.step = step;
+            split.value = split.clamp(value, f64::INFINITY);
+            split.collapse_threshold = settings.collapse_threshold;
+            split.hit_margin = settings.hit_margin;
+            split.touch_margin = settings.touch_margin;
+            split.cancel_button = settings.cancel_button;
+            split.drag_threshold = settings.drag_threshold;
+            split.double_click = settings.double_click;
+            split.double_click_time = settings.double_click_time;
+            split.snap_radius = settings.snap_radius;
+            split
+        };
         SplitLayoutController {
-            left: SplitController::new(settings.left_value, settings.left_min_value,
-                                       settings.border, SplitOrientation::Left),
-            right: SplitController::new(settings.right_value, settings.right_min_value,
-                                        settings.border, SplitOrientation::Right),
-            top: SplitController::new(settings.top_value, settings.top_min_value,
-                                      settings.border, SplitOrientation::Top),
-            bottom: SplitController::new(settings.bottom_value, settings.bottom_min_value,
-                                         settings.border, SplitOrientation::Bottom),
+            left: split(settings.left_value, settings.left_min_value, settings.left_max_value,
+                        settings.left_sizing, settings.left_step, SplitOrientation::Left),
+            right: split(settings.right_value, settings.right_min_value, settings.right_max_value,
+                         settings.right_sizing, settings.right_step, SplitOrientation::Right),
+            top: split(settings.top_value, settings.top_min_value, settings.top_max_value,
+                       settings.top_sizing, settings.top_step, SplitOrientation::Top),
+            bottom: split(settings.bottom_value, settings.bottom_min_value,
+                          settings.bottom_max_value, settings.bottom_sizing,
+                          settings.bottom_step, SplitOrientation::Bottom),
             center_min_size: settings.center_min_size,
+            shrink_policy: settings.shrink_policy,
+            corner_owners: settings.corner_owners,
+            keyboard_step: [settings.keyboard_step, settings.keyboard_large_step],
+            keyboard_focus: settings.keyboard_focus,
+            focus: None,
+            time: 0.0,
+            modifiers: ModifierKey::NO_MODIFIER,
+            cursor: [0.0; 2],
+            corner_hover: None,
+            corner_drag: None,
             drag_splits: 0,
             lock_splits: if settings.lock_left {LEFT} else {0} |
                          if settings.lock_right {RIGHT} else {0} |
                          if settings.lock_top {TOP} else {0} |
                          if settings.lock_bottom {BOTTOM} else {0},
+            pending_events: vec![],
         }
     }
 
     /// Handles event.
-    pub fn event<E: GenericEvent>(&mut self, rect: Rectangle, transform: Matrix2d, e: &E) {
+    ///
+    /// Returns notifications about what changed in the layout,
+    /// including changes from locking splits or restoring a snapshot since the last event.
+    /// Touch positions are normalized, so touch input requires the window size,
+    /// which is tracked from resize events or set with `set_touch_scale`.
+    /// Double clicks are timed by update events received by the layout,
+    /// including while splits are locked.
+    pub fn event<E: GenericEvent>(
+        &mut self,
+        rect: Rectangle,
+        transform: Matrix2d,
+        e: &E
+    ) -> Vec<SplitLayoutEvent> {
+        let old = self.event_states();
+        let old_focus = self.focus;
+
+        if let Some(args) = e.resize_args() {
+            self.set_touch_scale(args.window_size);
+        }
+        if let Some(args) = e.update_args() {
+            self.time += args.dt;
+        }
+
+        // Splits handle events at their shrunk values, which are not stored unless moved.
+        // Dragged splits do not shrink, such that they follow the cursor.
+        // Stored values are resized before shrinking, such that only they are quantized.
         let bounds = self.bounds(rect);
+        for &side in &SIDES {
+            self.split_mut(side).resize(f64::INFINITY, bounds);
+        }
+        let values = self.shrink(rect);
+        let mut stored = Sides([None; 4]);
+        for &side in &SIDES {
+            let split = self.split_mut(side);
+            split.moved = false;
+            if !split.is_collapsed() && !split.is_dragging() {
+                stored[side] = Some(split.value);
+                split.value = values[side];
+            }
+        }
+        // Splits snap to the shrunk values of other splits, unless collapsed.
+        let mut side_values = Sides([None; 4]);
+        for &side in &SIDES {
+            if !self.split(side).is_collapsed() {
+                side_values[side] = Some(values[side]);
+            }
+        }
+        for &side in &SIDES {
+            self.split_mut(side).side_values = side_values;
+        }
 
-        if (self.lock_splits & TOP) != TOP {
-            if self.drag_splits == 0 || (self.drag_splits & TOP) == TOP {
-                let layout = self.top_bottom_layout();
-                let max_value = bounds[3] - self.bottom.value - self.center_min_size[1] -
-                          self.top.border - self.bottom.border;
-                self.top.event(layout, max_value, bounds, transform, e);
+        self.modifiers.event(e);
+        if let Some(Button::Keyboard(key)) = e.press_args() {
+            self.key_press(key, bounds, stored);
+        }
+
+        if let Some(pos) = e.mouse_cursor_args() {
+            self.cursor = inside_pos(pos, transform);
+            if self.drag_splits == 0 {
+                let corners = self.corner_hit_rectangles(rect);
+                let cursor = self.cursor;
+                self.corner_hover = CORNERS.iter().cloned()
+                    .find(|&corner| {
+                        self.is_corner_enabled(corner) &&
+                        is_inside(cursor, corners[corner as usize])
+                    });
             }
         }
-        if (self.lock_splits & BOTTOM) != BOTTOM {
-            if self.drag_splits == 0 || (self.drag_splits & BOTTOM) == BOTTOM {
-                let layout = self.top_bottom_layout();
-                let max_value = bounds[3] - self.top.value - self.center_min_size[1] -
-                                self.bottom.border - self.top.border;
-                self.bottom.event(layout, max_value, bounds, transform, e);
+        if let (Some(Button::Mouse(MouseButton::Left)), Some(corner)) =
+            (e.press_args(), self.corner_hover) {
+            let cursor = self.cursor;
+            for &side in &corner.splits() {
+                self.split_mut(side).start_drag(cursor, bounds);
             }
+            self.corner_drag = Some(corner);
         }
-        if (self.lock_splits & LEFT) != LEFT {
-            if self.drag_splits == 0 || (self.drag_splits & LEFT) == LEFT {
-                let layout = self.left_right_layout(SplitLayoutPurpose::Event);
-                let max_value = bounds[2] - self.right.value - self.center_min_size[0] -
-                                self.left.border - self.right.border;
-                self.left.event(layout, max_value, bounds, transform, e);
+
+        for &side in &[Side::Top, Side::Bottom,
+                              Side::Left, Side::Right] {
+            let flag = flag(side);
+            let layout = self.layout(self.visible_values(), side,
+                                     SplitLayoutPurpose::Event);
+            let max_value = self.max_value(side, bounds, stored);
+            let locked = (self.lock_splits & flag) == flag;
+            let routed = self.drag_splits == 0 || (self.drag_splits & flag) == flag;
+            let time = self.time;
+            let split = self.split_mut(side);
+            if !locked && routed {
+                split.event_at(time, layout, max_value, bounds, transform, e);
             }
         }
-        if (self.lock_splits & RIGHT) != RIGHT {
-            if self.drag_splits == 0 || (self.drag_splits & RIGHT) == RIGHT {
-                let layout = self.left_right_layout(SplitLayoutPurpose::Event);
-                let max_value = bounds[2] - self.left.value - self.center_min_size[0] -
-                                self.right.border - self.left.border;
-                self.right.event(layout, max_value, bounds, transform, e);
+        for &side in &SIDES {
+            let split = self.split_mut(side);
+            match stored[side] {
+                // A drag starts from the shrunk value, but cancels to the stored value.
+                Some(value) if split.dragging => split.drag_start.0 = value,
+                Some(value) if !split.moved => split.value = value,
+                _ => {}
             }
         }
 
@@ -213,81 +681,573 @@ impl SplitLayoutController {
                           if self.bottom.is_dragging() {BOTTOM} else {0} |
                           if self.left.is_dragging() {LEFT} else {0} |
                           if self.right.is_dragging() {RIGHT} else {0};
+        if self.corner_drag.map(|corner| {
+            corner.splits().iter().any(|&side| {
+                let split = self.split(side);
+                !split.is_dragging() && !split.is_drag_pending()
+            })
+        }).unwrap_or(false) {
+            self.corner_drag = None;
+        }
+
+        let mut events = mem::take(&mut self.pending_events);
+        self.push_events(&old, old_focus, &mut events);
+        events
+    }
+
+    /// Captures split states for detecting changes.
+    fn event_states(&self) -> Vec<SplitEventState> {
+        SIDES.iter().map(|&side| SplitEventState::new(self.split(side))).collect()
+    }
+
+    /// Queues notifications about changes outside event handling for the next event.
+    fn queue_events(&mut self, old: &[SplitEventState], old_focus: Option<Side>) {
+        let mut events = mem::take(&mut self.pending_events);
+        self.push_events(old, old_focus, &mut events);
+        self.pending_events = events;
+    }
+
+    /// Pushes notifications about what changed since split states and focus were captured.
+    fn push_events(
+        &self,
+        old: &[SplitEventState],
+        old_focus: Option<Side>,
+        events: &mut Vec<SplitLayoutEvent>
+    ) {
+        for (&side, old) in SIDES.iter().zip(old.iter()) {
+            let new = SplitEventState::new(self.split(side));
+            if !old.dragging && new.dragging {
+                events.push(SplitLayoutEvent::DragStarted(side));
+            }
+            if old.value != new.value {
+                events.push(SplitLayoutEvent::ValueChanged {side, old: old.value, new: new.value});
+            }
+            if old.collapsed != new.collapsed {
+                events.push(SplitLayoutEvent::CollapseChanged {side, collapsed: new.collapsed});
+            }
+            if old.dragging && !new.dragging {
+                events.push(SplitLayoutEvent::DragEnded(side));
+            }
+            if old.hover != new.hover {
+                events.push(SplitLayoutEvent::HoverChanged {side, hover: new.hover});
+            }
+        }
+        if old_focus != self.focus {
+            events.push(SplitLayoutEvent::FocusChanged(self.focus));
+        }
     }
 
-    /// Returns the left/right split layout.
+    /// Captures split values, lock flags and collapse state.
+    pub fn snapshot(&self) -> SplitLayoutSnapshot {
+        let snapshot = |side: Side| {
+            let split = self.split(side);
+            SplitSnapshot {
+                value: split.value,
+                sizing: split.sizing,
+                locked: self.is_locked(side),
+                collapsed: split.collapsed,
+            }
+        };
+        SplitLayoutSnapshot {
+            left: snapshot(Side::Left),
+            right: snapshot(Side::Right),
+            top: snapshot(Side::Top),
+            bottom: snapshot(Side::Bottom),
+        }
+    }
+
+    /// Restores split values, lock flags and collapse state.
     ///
-    /// The left/right split layout depends on whether your purpose is to draw something or
-    /// handle events. When handling events, the rectangle overlaps with the top and bottom split.
-    pub fn left_right_layout(&self, purpose: SplitLayoutPurpose) -> SplitLayout {
-        let sign = purpose.sign();
-        SplitLayout {
-            start: self.top.value + sign * self.top.border,
-            end: self.bottom.value + sign * self.bottom.border
+    /// Any drag in progress is stopped, including corner drags.
+    /// Notifications about the changes are returned by the next event.
+    pub fn restore(&mut self, snapshot: &SplitLayoutSnapshot) {
+        let old = self.event_states();
+        let old_focus = self.focus;
+        let snapshots = [snapshot.left, snapshot.right, snapshot.top, snapshot.bottom];
+        self.lock_splits = 0;
+        self.drag_splits = 0;
+        self.corner_drag = None;
+        self.corner_hover = None;
+        for (&side, snapshot) in SIDES.iter().zip(snapshots.iter()) {
+            if snapshot.locked {
+                self.lock_splits |= flag(side);
+            }
+            let split = self.split_mut(side);
+            split.value = split.clamp(snapshot.value, f64::INFINITY);
+            split.sizing = snapshot.sizing;
+            split.stop_drag();
+            split.collapsed = snapshot.collapsed;
+        }
+        if let Some(focus) = self.focus {
+            if (self.lock_splits & flag(focus)) != 0 || self.split(focus).is_collapsed() {
+                self.set_focus(None);
+            }
         }
+        self.queue_events(&old, old_focus);
     }
 
-    /// Returns the top/bottom split layout.
-    pub fn top_bottom_layout(&self) -> SplitLayout {
-        SplitLayout {start: 0.0, end: 0.0}
+    /// Gets whether a split is locked.
+    pub fn is_locked(&self, side: Side) -> bool {
+        (self.lock_splits & flag(side)) != 0
     }
 
-    /// Computes split rectangles for drawing `[left, right, top, bottom]`.
-    pub fn rectangles(&self, rect: Rectangle) -> [Rectangle; 4] {
-        let bounds = self.bounds(rect);
-        let top_bottom_layout = self.top_bottom_layout();
-        let left_right_layout = self.left_right_layout(SplitLayoutPurpose::Draw);
+    /// Sets whether a split is locked.
+    ///
+    /// Locking a split cancels dragging it, including corner drags it is part of,
+    /// and removes keyboard focus from it.
+    /// Notifications about the canceled drag are returned by the next event.
+    pub fn set_locked(&mut self, side: Side, locked: bool) {
+        if !locked {
+            self.lock_splits &= !flag(side);
+            return;
+        }
+
+        let old = self.event_states();
+        let old_focus = self.focus;
+        self.lock_splits |= flag(side);
+        let mut cancel = vec![side];
+        if let Some(corner) = self.corner_drag {
+            if corner.splits().contains(&side) {
+                cancel.extend_from_slice(&corner.splits());
+                self.corner_drag = None;
+            }
+        }
+        if self.corner_hover.map(|corner| corner.splits().contains(&side)).unwrap_or(false) {
+            self.corner_hover = None;
+        }
+        for &side in &cancel {
+            let split = self.split_mut(side);
+            split.cancel_drag();
+            split.mouse_hover = false;
+            self.drag_splits &= !flag(side);
+        }
+        if self.focus == Some(side) {
+            self.set_focus(None);
+        }
+        self.queue_events(&old, old_focus);
+    }
+
+    /// Locks all splits, canceling any drag in progress.
+    pub fn lock_all(&mut self) {
+        for &side in &SIDES {
+            self.set_locked(side, true);
+        }
+    }
+
+    /// Unlocks all splits.
+    pub fn unlock_all(&mut self) {
+        self.lock_splits = 0;
+    }
+
+    /// Returns the values that dragged splits snap to `[left, right, top, bottom]`,
+    /// indexed by `Side`.
+    ///
+    /// This can be used to draw snap guides.
+    pub fn snap_targets(&self) -> Sides<Option<f64>> {
+        Sides([
+            self.left.snap_target(), self.right.snap_target(),
+            self.top.snap_target(), self.bottom.snap_target()
+        ])
+    }
+
+    /// Gets which split has keyboard focus.
+    pub fn focus(&self) -> Option<Side> {self.focus}
+
+    /// Sets the scale of normalized touch positions for all splits, usually the window size.
+    ///
+    /// This is required for touch input until the first resize event.
+    pub fn set_touch_scale(&mut self, touch_scale: [f64; 2]) {
+        for &side in &SIDES {
+            self.split_mut(side).touch_scale = touch_scale;
+        }
+    }
+
+    /// Sets which split has keyboard focus.
+    pub fn set_focus(&mut self, focus: Option<Side>) {
+        self.focus = focus;
+        for &side in &SIDES {
+            self.split_mut(side).set_focused(focus == Some(side));
+        }
+    }
+
+    /// Handles a key press.
+    ///
+    /// Tab and Shift-Tab cycles focus among unlocked splits, when enabled in settings.
+    /// Arrow keys move the focused split, Home and End moves it to the minimum or maximum value.
+    fn key_press(&mut self, key: Key, bounds: Rectangle, stored: Sides<Option<f64>>) {
+        let shift = self.modifiers.contains(ModifierKey::SHIFT);
+        if key == Key::Tab && self.keyboard_focus {
+            let focusable: Vec<Side> = SIDES.iter().cloned()
+                .filter(|&side| {
+                    (self.lock_splits & flag(side)) == 0 &&
+                    !self.split(side).is_collapsed()
+                })
+                .collect();
+            let n = focusable.len();
+            let focus = if n == 0 {
+                None
+            } else {
+                let i = match self.focus.and_then(|f| focusable.iter().position(|&o| o == f)) {
+                    Some(i) if shift => (i + n - 1) % n,
+                    Some(i) => (i + 1) % n,
+                    None if shift => n - 1,
+                    None => 0,
+                };
+                Some(focusable[i])
+            };
+            self.set_focus(focus);
+            return;
+        }
+
+        let side = match self.focus {
+            Some(side) => side,
+            None => return,
+        };
+        if (self.lock_splits & flag(side)) != 0 ||
+           self.split(side).is_collapsed() {
+            return;
+        }
+        let step = if shift {self.keyboard_step[1]} else {self.keyboard_step[0]};
+        let max_value = self.max_value(side, bounds, stored);
+        let split = self.split_mut(side);
+        // Move at least one grid step, since smaller steps are quantized back.
+        let step = step.max(split.step.unwrap_or(0.0));
+        let value = match (side, key) {
+            (Side::Left, Key::Right) |
+            (Side::Right, Key::Left) |
+            (Side::Top, Key::Down) |
+            (Side::Bottom, Key::Up) => split.value + step,
+            (Side::Left, Key::Left) |
+            (Side::Right, Key::Right) |
+            (Side::Top, Key::Up) |
+            (Side::Bottom, Key::Down) => split.value - step,
+            (_, Key::Home) => split.min_value,
+            (_, Key::End) => f64::INFINITY,
+            _ => return,
+        };
+        split.move_to(value, max_value, bounds);
+    }
+
+    /// Computes the maximum value of a split from bounds and stored values of shrunk splits.
+    ///
+    /// The split can not get closer to the opposite split than the minimum size of center.
+    /// When the opposite split is shrunk, it can shrink further down to its minimum value.
+    fn max_value(&self, side: Side, bounds: Rectangle, stored: Sides<Option<f64>>) -> f64 {
+        let opposite_side = match side {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+            Side::Top => Side::Bottom,
+            Side::Bottom => Side::Top,
+        };
+        let (split, opposite) = (self.split(side), self.split(opposite_side));
+        let (size, center_min_size) = match side {
+            Side::Left | Side::Right => (bounds[2], self.center_min_size[0]),
+            Side::Top | Side::Bottom => (bounds[3], self.center_min_size[1]),
+        };
+        let opposite_value = match stored[opposite_side] {
+            Some(value) if opposite.value < value => opposite.value.min(opposite.min_value),
+            _ => opposite.visible_value(),
+        };
+        size - opposite_value - center_min_size - split.border - opposite.visible_border()
+    }
+
+    /// Gets the split controller of a side.
+    pub fn split(&self, side: Side) -> &SplitController {
+        match side {
+            Side::Left => &self.left,
+            Side::Right => &self.right,
+            Side::Top => &self.top,
+            Side::Bottom => &self.bottom,
+        }
+    }
+
+    /// Gets the split controller of a side mutably.
+    pub fn split_mut(&mut self, side: Side) -> &mut SplitController {
+        match side {
+            Side::Left => &mut self.left,
+            Side::Right => &mut self.right,
+            Side::Top => &mut self.top,
+            Side::Bottom => &mut self.bottom,
+        }
+    }
+
+    /// Returns the left/right split layouts `[left, right]`.
+    ///
+    /// The left/right split layout depends on whether your purpose is to draw something or
+    /// handle events. When handling events, the rectangle overlaps with the top and bottom split
+    /// at corners owned by the top and bottom panels.
+    pub fn left_right_layout(&self, purpose: SplitLayoutPurpose) -> [SplitLayout; 2] {
+        let values = self.visible_values();
+        [
+            self.layout(values, Side::Left, purpose),
+            self.layout(values, Side::Right, purpose),
+        ]
+    }
+
+    /// Returns the top/bottom split layouts `[top, bottom]`.
+    ///
+    /// The top/bottom split layout depends on whether your purpose is to draw something or
+    /// handle events. When handling events, the rectangle overlaps with the left and right split
+    /// at corners owned by the left and right panels.
+    pub fn top_bottom_layout(&self, purpose: SplitLayoutPurpose) -> [SplitLayout; 2] {
+        let values = self.visible_values();
         [
-            self.left.line_rect(left_right_layout, bounds),
-            self.right.line_rect(left_right_layout, bounds),
-            self.top.line_rect(top_bottom_layout, bounds),
-            self.bottom.line_rect(top_bottom_layout, bounds),
+            self.layout(values, Side::Top, purpose),
+            self.layout(values, Side::Bottom, purpose),
         ]
     }
 
-    /// Returns the split controller states `[left, right, top, bottom]`.
-    pub fn states(&self) -> [SplitState; 4] {
-        [self.left.state(), self.right.state(), self.top.state(), self.bottom.state()]
+    /// Computes split layout from split values `[left, right, top, bottom]`.
+    ///
+    /// A split starts or ends at a perpendicular split where the corner is owned by the other
+    /// panels, otherwise it spans to the edge of bounds.
+    fn layout(
+        &self,
+        values: Sides<f64>,
+        side: Side,
+        purpose: SplitLayoutPurpose
+    ) -> SplitLayout {
+        let sign = purpose.sign();
+        let (corners, owner, splits) = match side {
+            Side::Left => ([Corner::TopLeft, Corner::BottomLeft],
+                CornerOwner::TopBottom, [Side::Top, Side::Bottom]),
+            Side::Right => ([Corner::TopRight, Corner::BottomRight],
+                CornerOwner::TopBottom, [Side::Top, Side::Bottom]),
+            Side::Top => ([Corner::TopLeft, Corner::TopRight],
+                CornerOwner::LeftRight, [Side::Left, Side::Right]),
+            Side::Bottom => ([Corner::BottomLeft, Corner::BottomRight],
+                CornerOwner::LeftRight, [Side::Left, Side::Right]),
+        };
+        let offset = |i: usize| {
+            if self.corner_owners[corners[i] as usize] == owner {
+                let split = splits[i];
+                values[split] + sign * self.split(split).visible_border()
+            } else {
+                0.0
+            }
+        };
+        SplitLayout {start: offset(0), end: offset(1)}
     }
 
-    /// Computes panel rectangles for layout `[left, right, top, bottom, center]`.
-    pub fn panel_rectangles(&self, rect: Rectangle) -> [Rectangle; 5] {
+    /// Computes split rectangles for drawing `[left, right, top, bottom]`, indexed by `Side`.
+    pub fn rectangles(&self, rect: Rectangle) -> Sides<Rectangle> {
         let bounds = self.bounds(rect);
-        let left_right_y = bounds[1] + self.top.value + self.top.border;
-        let left_right_h = bounds[3] - self.top.value - self.top.border -
-                           self.bottom.value - self.bottom.border;
+        let values = self.shrink(rect);
+        let line_rect = |side: Side| {
+            let split = self.split(side);
+            let layout = self.layout(values, side, SplitLayoutPurpose::Draw);
+            split.line_rect_at(values[side], split.visible_border(), layout, bounds)
+        };
+        Sides([
+            line_rect(Side::Left), line_rect(Side::Right),
+            line_rect(Side::Top), line_rect(Side::Bottom),
+        ])
+    }
+
+    /// Returns the split controller states `[left, right, top, bottom]`, indexed by `Side`.
+    ///
+    /// Splits dragged together at a corner have the state `CornerDrag`.
+    /// Locked splits have the state `Locked`.
+    pub fn states(&self) -> Sides<SplitState> {
+        let mut states = Sides([
+            self.left.state(), self.right.state(), self.top.state(), self.bottom.state()
+        ]);
+        if let Some(corner) = self.corner_drag {
+            for &side in &corner.splits() {
+                states[side] = SplitState::CornerDrag;
+            }
+        }
+        for &side in &SIDES {
+            if self.is_locked(side) {
+                states[side] = SplitState::Locked;
+            }
+        }
+        states
+    }
+
+    /// Computes corner rectangles for drawing `[top_left, top_right, bottom_left, bottom_right]`.
+    ///
+    /// A corner rectangle is where two perpendicular split lines meet.
+    pub fn corner_rectangles(&self, rect: Rectangle) -> [Rectangle; 4] {
+        let lines = self.rectangles(rect);
+        let corner = |corner: Corner| {
+            let [vertical, horizontal] = corner.splits();
+            let v = lines[vertical];
+            let h = lines[horizontal];
+            [v[0], h[1], v[2], h[3]]
+        };
+        [
+            corner(Corner::TopLeft), corner(Corner::TopRight),
+            corner(Corner::BottomLeft), corner(Corner::BottomRight)
+        ]
+    }
+
+    /// Returns the corner states `[top_left, top_right, bottom_left, bottom_right]`.
+    pub fn corner_states(&self) -> [SplitState; 4] {
+        let state = |corner| {
+            if self.corner_drag == Some(corner) {SplitState::CornerDrag}
+            else if self.corner_hover == Some(corner) {SplitState::Hover}
+            else {SplitState::Inactive}
+        };
         [
-            [bounds[0], left_right_y, self.left.value, left_right_h],
-            [bounds[0] + bounds[2] - self.right.value, left_right_y,
-             self.right.value, left_right_h],
-            [bounds[0], bounds[1], bounds[2], self.top.value],
-            [bounds[0], bounds[1] + bounds[3] - self.bottom.value, bounds[2], self.bottom.value],
-            [bounds[0] + self.left.value + self.left.border, left_right_y,
-             bounds[2] - self.right.value - self.right.border -
-             self.left.value - self.left.border, left_right_h],
+            state(Corner::TopLeft), state(Corner::TopRight),
+            state(Corner::BottomLeft), state(Corner::BottomRight)
         ]
     }
 
+    /// Computes corner rectangles expanded by the mouse margin of splits.
+    fn corner_hit_rectangles(&self, rect: Rectangle) -> [Rectangle; 4] {
+        let mut corners = self.corner_rectangles(rect);
+        for (&corner, r) in CORNERS.iter().zip(corners.iter_mut()) {
+            let [vertical, horizontal] = corner.splits();
+            let mx = self.split(vertical).hit_margin;
+            let my = self.split(horizontal).hit_margin;
+            *r = margin_rect(*r, [mx, my]);
+        }
+        corners
+    }
+
+    /// Gets whether both splits of a corner can be dragged.
+    fn is_corner_enabled(&self, corner: Corner) -> bool {
+        corner.splits().iter().all(|&side| {
+            (self.lock_splits & flag(side)) == 0 &&
+            !self.split(side).is_collapsed()
+        })
+    }
+
+    /// Computes panel rectangles for layout `[left, right, top, bottom, center]`,
+    /// indexed by `PanelId`.
+    ///
+    /// Collapsed panels get zero size.
+    pub fn panel_rectangles(&self, rect: Rectangle) -> Panels<Rectangle> {
+        let bounds = self.bounds(rect);
+        let values = self.shrink(rect);
+        let Sides([left, right, top, bottom]) = values;
+        let layout = |side| self.layout(values, side, SplitLayoutPurpose::Draw);
+        let (l, r) = (layout(Side::Left), layout(Side::Right));
+        let (t, b) = (layout(Side::Top), layout(Side::Bottom));
+        let center_x = bounds[0] + left + self.left.visible_border();
+        let center_y = bounds[1] + top + self.top.visible_border();
+        Panels([
+            [bounds[0], bounds[1] + l.start, left, bounds[3] - l.start - l.end],
+            [bounds[0] + bounds[2] - right, bounds[1] + r.start, right,
+             bounds[3] - r.start - r.end],
+            [bounds[0] + t.start, bounds[1], bounds[2] - t.start - t.end, top],
+            [bounds[0] + b.start, bounds[1] + bounds[3] - bottom,
+             bounds[2] - b.start - b.end, bottom],
+            [center_x, center_y,
+             bounds[0] + bounds[2] - right - self.right.visible_border() - center_x,
+             bounds[1] + bounds[3] - bottom - self.bottom.visible_border() - center_y],
+        ])
+    }
+
+    /// Computes the rectangle of a panel for layout.
+    pub fn panel_rect(&self, panel: PanelId, rect: Rectangle) -> Rectangle {
+        self.panel_rectangles(rect)[panel]
+    }
+
+    /// Returns the sides with split rectangles for drawing and split states.
+    pub fn sides(&self, rect: Rectangle) -> impl Iterator<Item = (Side, Rectangle, SplitState)> {
+        let rectangles = self.rectangles(rect);
+        let states = self.states();
+        SIDES.iter().map(move |&side| (side, rectangles[side], states[side]))
+    }
+
     /// Computes the minimum size using current values in split controls.
     ///
     /// The current values in the split controls are used instead of the minimum values,
     /// because the splits should appear visually with the same current value.
+    /// Collapsed splits are ignored.
     pub fn min_size(&self) -> [f64; 2] {
         [
-            self.left.value + self.left.border + self.right.value + self.right.border +
+            self.left.visible_value() + self.left.visible_border() +
+            self.right.visible_value() + self.right.visible_border() +
             self.center_min_size[0],
-            self.top.value + self.top.border + self.bottom.value + self.bottom.border +
+            self.top.visible_value() + self.top.visible_border() +
+            self.bottom.visible_value() + self.bottom.visible_border() +
             self.center_min_size[1]
         ]
     }
 
     /// Computes the bounds from window bounds `[x, y, w, h]`.
     ///
-    /// Does not get less in size than specified by `min_size`.
+    /// Splits shrink according to the shrink policy,
+    /// but the bounds does not get less in size than the shrunk layout.
     pub fn bounds(&self, rect: Rectangle) -> Rectangle {
-        let min_size = self.min_size();
+        let Sides([left, right, top, bottom]) = self.shrink(rect);
+        let min_size = [
+            left + self.left.visible_border() + right + self.right.visible_border() +
+            self.center_min_size[0],
+            top + self.top.visible_border() + bottom + self.bottom.visible_border() +
+            self.center_min_size[1]
+        ];
         [rect[0], rect[1], rect[2].max(min_size[0]), rect[3].max(min_size[1])]
     }
+
+    /// Gets visible split values `[left, right, top, bottom]`.
+    fn visible_values(&self) -> Sides<f64> {
+        Sides([
+            self.left.visible_value(), self.right.visible_value(),
+            self.top.visible_value(), self.bottom.visible_value()
+        ])
+    }
+
+    /// Computes visible split values `[left, right, top, bottom]` after shrinking to fit
+    /// window bounds `[x, y, w, h]`.
+    ///
+    /// Fractional splits compute their value from the window bounds,
+    /// such that they are sized correctly before handling events.
+    /// Dragged splits keep their value, such that they follow the cursor.
+    fn shrink(&self, rect: Rectangle) -> Sides<f64> {
+        let mut values = self.visible_values();
+        for &side in &SIDES {
+            let split = self.split(side);
+            if let (SplitSizing::Fraction(fraction), false) = (split.sizing, split.collapsed) {
+                values[side] = split.clamp(fraction * split.extent(rect), f64::INFINITY);
+            }
+        }
+        let axes = [
+            (Side::Left, Side::Right, [&self.left, &self.right], rect[2], self.center_min_size[0]),
+            (Side::Top, Side::Bottom, [&self.top, &self.bottom], rect[3], self.center_min_size[1]),
+        ];
+        for &(a, b, splits, size, center_min_size) in &axes {
+            let mut excess = values[a] + values[b] + center_min_size - size +
+                             splits[0].visible_border() + splits[1].visible_border();
+            if excess <= 0.0 {continue};
+
+            let slack = |i: Side, split: &SplitController| {
+                if split.is_collapsed() || split.is_dragging() {0.0}
+                else {(values[i] - split.min_value).max(0.0)}
+            };
+            let slack = [slack(a, splits[0]), slack(b, splits[1])];
+            match self.shrink_policy {
+                ShrinkPolicy::Overflow => {}
+                ShrinkPolicy::Proportional => {
+                    let total = slack[0] + slack[1];
+                    if total > 0.0 {
+                        let factor = (excess / total).min(1.0);
+                        values[a] -= factor * slack[0];
+                        values[b] -= factor * slack[1];
+                    }
+                }
+                ShrinkPolicy::Priority(order) => {
+                    for &side in &order {
+                        let (i, slack) = if side == a {
+                            (a, slack[0])
+                        } else if side == b {
+                            (b, slack[1])
+                        } else {
+                            continue
+                        };
+                        let d = excess.min(slack);
+                        values[i] -= d;
+                        excess -= d;
+                    }
+                }
+            }
+        }
+        values
+    }
 }
 
 /// Stores information about an UI split.
@@ -296,18 +1256,92 @@ pub struct SplitController {
     mouse_hover: bool,
     /// Whether the user is dragging the split.
     dragging: bool,
+    /// The last position of mouse cursor.
+    cursor: Vec2d,
+    /// The offset from split to the position where the user grabbed it.
+    grab_offset: f64,
+    /// The value, sizing and collapse state when the user started dragging.
+    drag_start: (f64, SplitSizing, bool),
+    /// Whether the value was moved by input since split layout last cleared it.
+    moved: bool,
     /// The value of split.
     pub value: f64,
     /// The minimum value of split.
     pub min_value: f64,
+    /// The maximum value of split.
+    pub max_value: f64,
     /// The border width.
     pub border: f64,
     /// The orientation of split.
     pub orientation: SplitOrientation,
+    /// The sizing of split.
+    pub sizing: SplitSizing,
+    /// The size of parent panel along the axis of split, from the last resize.
+    parent_extent: f64,
+    /// The distance beyond the minimum value for collapsing the split when dragging.
+    pub collapse_threshold: Option<f64>,
+    /// Whether the split is collapsed.
+    collapsed: bool,
+    /// Whether the split collapses when the user stops dragging.
+    collapse_pending: bool,
+    /// Whether the split has keyboard focus.
+    focused: bool,
+    /// The margin around the split for mouse input.
+    ///
+    /// This makes thin splits easier to grab without changing how they are drawn.
+    pub hit_margin: f64,
+    /// The margin around the split for touch input.
+    pub touch_margin: f64,
+    /// The button that cancels dragging, in addition to Escape.
+    pub cancel_button: Option<Button>,
+    /// The distance the cursor must move after pressing before dragging starts.
+    ///
+    /// This keeps clicks on the split from changing the value.
+    pub drag_threshold: f64,
+    /// The position where the user pressed, while waiting for the drag threshold.
+    drag_pending: Option<Vec2d>,
+    /// The value restored by `DoubleClickAction::ResetToDefault`.
+    pub default_value: f64,
+    /// The size of content in split panel, used by `DoubleClickAction::FitToContent`.
+    pub content_size: Option<f64>,
+    /// The action when double clicking the split.
+    pub double_click: Option<DoubleClickAction>,
+    /// The maximum time in seconds between two clicks of a double click.
+    pub double_click_time: f64,
+    /// The time in seconds, from update events.
+    time: f64,
+    /// The time of last click on the split.
+    last_press: Option<f64>,
+    /// The values that the split snaps to while dragging.
+    pub snap_points: Vec<SnapPoint>,
+    /// The distance within which the split snaps to a snap point.
+    pub snap_radius: f64,
+    /// The value that the split snaps to.
+    snap_target: Option<f64>,
+    /// The visible values of splits in a layout `[left, right, top, bottom]`,
+    /// for snapping to other splits.
+    side_values: Sides<Option<f64>>,
+    /// The grid step of values.
+    ///
+    /// Values are quantized to the nearest multiple of step within the minimum and maximum value.
+    pub step: Option<f64>,
+    /// Scales normalized touch positions to window coordinates.
+    ///
+    /// This is updated from resize events, but must be set to the window size initially
+    /// for touch input to work before the first resize event.
+    pub touch_scale: [f64; 2],
+    /// The touch device and id dragging the split.
+    touch: Option<(i64, i64)>,
 }
 
 impl SplitController {
-    /// Creates a new `SplitController`.
+    /// Creates a new `SplitController` without maximum value.
+    ///
+    /// The mouse margin is set to 0 and the touch margin is set to 8.
+    /// Dragging starts immediately when pressing the split.
+    /// The default value is set to the value.
+    /// There are no snap points, and the snap radius is set to 8.
+    /// Values are not quantized to a grid step.
     pub fn new(
         value: f64,
         min_value: f64,
@@ -317,69 +1351,430 @@ impl SplitController {
         SplitController {
             mouse_hover: false,
             dragging: false,
-            value: value,
-            min_value: min_value,
-            border: border,
-            orientation: orientation,
+            cursor: [0.0; 2],
+            grab_offset: 0.0,
+            drag_start: (value, SplitSizing::Absolute, false),
+            moved: false,
+            value,
+            min_value,
+            max_value: f64::INFINITY,
+            border,
+            orientation,
+            sizing: SplitSizing::Absolute,
+            parent_extent: 0.0,
+            collapse_threshold: None,
+            collapsed: false,
+            collapse_pending: false,
+            focused: false,
+            hit_margin: 0.0,
+            touch_margin: 8.0,
+            cancel_button: None,
+            drag_threshold: 0.0,
+            drag_pending: None,
+            default_value: value,
+            content_size: None,
+            double_click: None,
+            double_click_time: 0.5,
+            time: 0.0,
+            last_press: None,
+            snap_points: vec![],
+            snap_radius: 8.0,
+            snap_target: None,
+            side_values: Sides([None; 4]),
+            step: None,
+            touch_scale: [1.0; 2],
+            touch: None,
         }
     }
 
     /// Gets whether the split is currently being dragged by the user.
     pub fn is_dragging(&self) -> bool {self.dragging}
 
-    /// Handles event.
-    pub fn event<E: GenericEvent>(
-        &mut self,
-        layout: SplitLayout,
-        max_value: f64,
-        rect: Rectangle,
-        transform: Matrix2d,
-        e: &E
-    ) {
-        if let Some(pos) = e.mouse_cursor_args() {
-            let pos = inside_pos(pos, transform);
-            if self.dragging {
-                match self.orientation {
-                    SplitOrientation::Left => {
-                        self.value = (pos[0] - rect[0] - 0.5 * self.border)
-                            .max(self.min_value)
-                            .min(max_value);
-                    }
-                    SplitOrientation::Right => {
-                        self.value = (rect[2] - pos[0] + rect[0] - 0.5 * self.border)
-                            .max(self.min_value)
-                            .min(max_value);
+    /// Gets the value that the dragged split snaps to.
+    pub fn snap_target(&self) -> Option<f64> {self.snap_target}
+
+    /// Gets whether the split is pressed, but not moved beyond the drag threshold.
+    pub fn is_drag_pending(&self) -> bool {self.drag_pending.is_some()}
+
+    /// Sets the value of split, clamped to the minimum and maximum value.
+    ///
+    /// Fractional splits update their fraction from the last size of parent panel.
+    pub fn set_value(&mut self, value: f64) {
+        self.value = self.clamp(value, f64::INFINITY);
+        self.store_fraction();
+    }
+
+    /// Moves split to a new value within rectangle `[x, y, w, h]` of parent panel.
+    ///
+    /// The value is clamped to the minimum and maximum value.
+    /// Fractional splits update their fraction.
+    pub fn move_to(&mut self, value: f64, max_value: f64, rect: Rectangle) {
+        self.value = self.clamp(value, max_value);
+        self.moved = true;
+        self.update_fraction(rect);
+    }
+
+    /// Gets whether the split has keyboard focus.
+    pub fn is_focused(&self) -> bool {self.focused}
+
+    /// Sets whether the split has keyboard focus.
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Gets whether the split is collapsed.
+    pub fn is_collapsed(&self) -> bool {self.collapsed}
+
+    /// Collapses the split, hiding its panel and border.
+    ///
+    /// The value is kept, such that it is restored when expanding the split.
+    pub fn collapse(&mut self) {
+        self.collapsed = true;
+        self.mouse_hover = false;
+        self.stop_drag();
+    }
+
+    /// Expands the split to its last value.
+    pub fn expand(&mut self) {
+        self.collapsed = false;
+    }
+
+    /// Gets the visible value of split, which is zero when collapsed.
+    pub fn visible_value(&self) -> f64 {
+        if self.collapsed {0.0} else {self.value}
+    }
+
+    /// Gets the visible border width, which is zero when collapsed.
+    pub fn visible_border(&self) -> f64 {
+        if self.collapsed {0.0} else {self.border}
+    }
+
+    /// Handles event.
+    ///
+    /// The time for double clicks advances with update events received by the split.
+    pub fn event<E: GenericEvent>(
+        &mut self,
+        layout: SplitLayout,
+        max_value: f64,
+        rect: Rectangle,
+        transform: Matrix2d,
+        e: &E
+    ) {
+        if let Some(args) = e.update_args() {
+            self.time += args.dt;
+        }
+        let time = self.time;
+        self.resize(max_value, rect);
+        self.event_at(time, layout, max_value, rect, transform, e);
+    }
+
+    /// Handles event at a time in seconds, used when events have no time stamp.
+    ///
+    /// The split is expected to be resized to the parent panel.
+    fn event_at<E: GenericEvent>(
+        &mut self,
+        time: f64,
+        layout: SplitLayout,
+        max_value: f64,
+        rect: Rectangle,
+        transform: Matrix2d,
+        e: &E
+    ) {
+        if let Some(args) = e.resize_args() {
+            self.touch_scale = args.window_size;
+        }
+
+        if self.collapsed && self.collapse_threshold.is_none() &&
+           self.double_click != Some(DoubleClickAction::ToggleCollapse) {return};
+
+        if let Some(pos) = e.mouse_cursor_args() {
+            let pos = inside_pos(pos, transform);
+            self.cursor = pos;
+            if self.touch.is_none() {
+                self.drag_to(pos, max_value, rect);
+            }
+            let hit_rect = self.hit_rect(layout, rect, self.hit_margin);
+            self.mouse_hover = is_inside(pos, hit_rect);
+        }
+
+        if let Some(args) = e.touch_args() {
+            let pos = args.position();
+            let pos = [pos[0] * self.touch_scale[0], pos[1] * self.touch_scale[1]];
+            let pos = inside_pos(pos, transform);
+            let id = Some((args.device, args.id));
+            let hit_rect = self.hit_rect(layout, rect, self.touch_margin);
+            match args.touch {
+                Touch::Start => {
+                    if !self.dragging && self.drag_pending.is_none() &&
+                       self.can_drag() && is_inside(pos, hit_rect) {
+                        self.start_drag(pos, rect);
+                        self.touch = id;
+                        self.mouse_hover = true;
                     }
-                    SplitOrientation::Top => {
-                        self.value = (pos[1] - rect[1] - 0.5 * self.border)
-                            .max(self.min_value)
-                            .min(max_value);
+                }
+                Touch::Move => {
+                    if self.touch == id {
+                        self.drag_to(pos, max_value, rect);
+                        let hit_rect = self.hit_rect(layout, rect, self.touch_margin);
+                        self.mouse_hover = is_inside(pos, hit_rect);
                     }
-                    SplitOrientation::Bottom => {
-                        self.value = (rect[1] + rect[3] - pos[1] - 0.5 * self.border)
-                            .max(self.min_value)
-                            .min(max_value);
+                }
+                Touch::End | Touch::Cancel => {
+                    if self.touch == id {
+                        self.release();
+                        self.mouse_hover = false;
                     }
                 }
             }
-            let line_rect = self.line_rect(layout, rect);
-            self.mouse_hover = is_inside(pos, line_rect);
+        }
+
+        if let Some(button) = e.press_args() {
+            if button == Button::Keyboard(Key::Escape) || Some(button) == self.cancel_button {
+                self.cancel_drag();
+            }
         }
 
         if let Some(Button::Mouse(MouseButton::Left)) = e.press_args() {
-            if self.mouse_hover {
-                self.dragging = true;
+            if self.mouse_hover && self.touch.is_none() {
+                let time = e.time_stamp().map(|t| t as f64 / 1000.0).unwrap_or(time);
+                let double_click = self.last_press
+                    .map(|last| time - last <= self.double_click_time).unwrap_or(false);
+                match self.double_click {
+                    Some(action) if double_click => {
+                        self.last_press = None;
+                        self.double_click_action(action, max_value, rect);
+                    }
+                    _ => {
+                        self.last_press = Some(time);
+                        if self.can_drag() {
+                            let pos = self.cursor;
+                            self.start_drag(pos, rect);
+                        }
+                    }
+                }
+            } else {
+                self.last_press = None;
             }
         }
 
         if let Some(Button::Mouse(MouseButton::Left)) = e.release_args() {
-            self.dragging = false;
+            if self.touch.is_none() {
+                self.release();
+            }
+        }
+    }
+
+    /// Gets whether the split can be dragged.
+    ///
+    /// A collapsed split can only be dragged open with a collapse threshold.
+    fn can_drag(&self) -> bool {
+        !self.collapsed || self.collapse_threshold.is_some()
+    }
+
+    /// Starts dragging from position of mouse cursor or touch.
+    ///
+    /// The offset between the position and the split is kept while dragging.
+    /// With a drag threshold, the drag is pending until the position moves beyond it.
+    fn start_drag(&mut self, pos: Vec2d, rect: Rectangle) {
+        if self.drag_threshold > 0.0 {
+            self.drag_pending = Some(pos);
+        } else {
+            self.dragging = true;
+        }
+        self.drag_start = (self.value, self.sizing, self.collapsed);
+        self.grab_offset = self.distance(pos, rect) - self.visible_value();
+    }
+
+    /// Performs the action when double clicking the split.
+    fn double_click_action(&mut self, action: DoubleClickAction, max_value: f64, rect: Rectangle) {
+        match action {
+            DoubleClickAction::ResetToDefault => {
+                self.expand();
+                let value = self.default_value;
+                self.move_to(value, max_value, rect);
+            }
+            DoubleClickAction::ToggleCollapse => {
+                if self.collapsed {self.expand()} else {self.collapse()}
+            }
+            DoubleClickAction::FitToContent => {
+                if let Some(content_size) = self.content_size {
+                    self.expand();
+                    self.move_to(content_size, max_value, rect);
+                }
+            }
+        }
+    }
+
+    /// Drags split to position of mouse cursor or touch.
+    ///
+    /// Starts a pending drag when the position is beyond the drag threshold.
+    fn drag_to(&mut self, pos: Vec2d, max_value: f64, rect: Rectangle) {
+        if let Some(press) = self.drag_pending {
+            let (dx, dy) = (pos[0] - press[0], pos[1] - press[1]);
+            if (dx * dx + dy * dy).sqrt() > self.drag_threshold {
+                self.drag_pending = None;
+                self.dragging = true;
+            }
+        }
+        if !self.dragging {return};
+
+        let value = self.distance(pos, rect) - self.grab_offset;
+        self.drag(value, max_value);
+        self.snap(max_value, rect);
+        self.update_fraction(rect);
+    }
+
+    /// Snaps the value to the nearest snap point within the snap radius.
+    ///
+    /// Snap points outside the minimum and maximum value are ignored.
+    fn snap(&mut self, max_value: f64, rect: Rectangle) {
+        self.snap_target = None;
+        if self.collapsed || self.collapse_pending {return};
+
+        let extent = self.extent(rect);
+        let mut nearest = self.snap_radius;
+        for point in &self.snap_points {
+            let target = match *point {
+                SnapPoint::Value(value) => value,
+                SnapPoint::Fraction(fraction) => fraction * extent,
+                SnapPoint::Default => self.default_value,
+                SnapPoint::Split(side) => match self.side_values[side] {
+                    Some(value) if side != Side::from(self.orientation) => value,
+                    _ => continue,
+                },
+            };
+            let distance = (target - self.value).abs();
+            if distance <= nearest && self.clamp(target, max_value) == target {
+                nearest = distance;
+                self.snap_target = Some(target);
+            }
+        }
+        if let Some(target) = self.snap_target {
+            self.value = target;
+        }
+    }
+
+    /// Gets the distance from the edge of parent panel to a position.
+    fn distance(&self, pos: Vec2d, rect: Rectangle) -> f64 {
+        match self.orientation {
+            SplitOrientation::Left => pos[0] - rect[0],
+            SplitOrientation::Right => rect[0] + rect[2] - pos[0],
+            SplitOrientation::Top => pos[1] - rect[1],
+            SplitOrientation::Bottom => rect[1] + rect[3] - pos[1],
+        }
+    }
+
+    /// Stops dragging, collapsing the split when pending.
+    ///
+    /// A collapsed split keeps the value from when the user started dragging.
+    fn release(&mut self) {
+        if self.collapse_pending {
+            let (value, sizing, _) = self.drag_start;
+            self.value = value;
+            self.sizing = sizing;
+            self.collapse();
+        }
+        self.stop_drag();
+    }
+
+    /// Cancels dragging and restores the state from when the user started dragging.
+    pub fn cancel_drag(&mut self) {
+        if self.dragging || self.drag_pending.is_some() {
+            let (value, sizing, collapsed) = self.drag_start;
+            self.value = value;
+            self.moved = true;
+            self.sizing = sizing;
+            self.collapsed = collapsed;
+            self.stop_drag();
+        }
+    }
+
+    /// Stops dragging without collapsing the split.
+    fn stop_drag(&mut self) {
+        self.dragging = false;
+        self.snap_target = None;
+        self.drag_pending = None;
+        self.collapse_pending = false;
+        self.touch = None;
+    }
+
+    /// Updates the value from rectangle `[x, y, w, h]` of parent panel.
+    ///
+    /// Fractional splits compute their value from the size of parent panel.
+    /// The value is clamped to the minimum and maximum value, and quantized to the grid step.
+    pub fn resize(&mut self, max_value: f64, rect: Rectangle) {
+        self.parent_extent = self.extent(rect);
+        self.value = match self.sizing {
+            SplitSizing::Fraction(fraction) => self.clamp(fraction * self.extent(rect), max_value),
+            SplitSizing::Absolute => self.clamp(self.value, max_value),
+        };
+    }
+
+    /// Updates the fraction of a fractional split from its value.
+    fn update_fraction(&mut self, rect: Rectangle) {
+        self.parent_extent = self.extent(rect);
+        self.store_fraction();
+    }
+
+    /// Stores the fraction of a fractional split from its value and the last size of parent panel.
+    fn store_fraction(&mut self) {
+        let extent = self.parent_extent;
+        if let (SplitSizing::Fraction(_), true) = (self.sizing, extent > 0.0) {
+            self.sizing = SplitSizing::Fraction(self.value / extent);
+        }
+    }
+
+    /// Clamps a value to the minimum and maximum value.
+    ///
+    /// With a grid step, the value is quantized to the nearest multiple of step within range.
+    fn clamp(&self, value: f64, max_value: f64) -> f64 {
+        let value = value.max(self.min_value).min(self.max_value).min(max_value);
+        match self.step {
+            Some(step) if step > 0.0 => {
+                let (min, max) = (self.min_value, self.max_value.min(max_value));
+                let quantized = (value / step).round() * step;
+                let quantized = if quantized < min {(min / step).ceil() * step}
+                    else if quantized > max {(max / step).floor() * step}
+                    else {quantized};
+                if quantized >= min && quantized <= max {quantized} else {value}
+            }
+            _ => value,
+        }
+    }
+
+    /// Gets the size of parent panel along the axis of split.
+    fn extent(&self, rect: Rectangle) -> f64 {
+        match self.orientation {
+            SplitOrientation::Left | SplitOrientation::Right => rect[2],
+            SplitOrientation::Top | SplitOrientation::Bottom => rect[3],
+        }
+    }
+
+    /// Drags split to a new value.
+    ///
+    /// When the value is beyond the collapse threshold, the split is collapsed on release.
+    /// A collapsed split reopens when dragged out past the threshold.
+    fn drag(&mut self, value: f64, max_value: f64) {
+        let collapse = match self.collapse_threshold {
+            Some(threshold) => value < self.min_value - threshold,
+            None => false,
+        };
+        if self.collapsed && !collapse {
+            self.collapsed = false;
+        }
+        self.collapse_pending = collapse && !self.collapsed;
+        if !self.collapsed {
+            self.value = self.clamp(value, max_value);
+            self.moved = true;
         }
     }
 
     /// Gets the current state of split.
     pub fn state(&self) -> SplitState {
+        if self.collapse_pending {return SplitState::CollapsePending};
+
         match (self.mouse_hover, self.dragging) {
+            (false, false) if self.focused => SplitState::Focused,
             (false, false) => SplitState::Inactive,
             (true, false) => SplitState::Hover,
             (true, true) => SplitState::Drag,
@@ -389,22 +1784,49 @@ impl SplitController {
 
     /// Gets line rectangle `[x, y, w, h]` from rectangle `[x, y, w, h]` of parent panel.
     pub fn line_rect(&self, layout: SplitLayout, rect: Rectangle) -> Rectangle {
+        self.line_rect_at(self.visible_value(), self.visible_border(), layout, rect)
+    }
+
+    /// Gets the rectangle used to detect whether the mouse or touch is over the split.
+    ///
+    /// The line rectangle is expanded across the split by a margin.
+    /// A collapsed split can be dragged open at the edge of parent panel,
+    /// when it has a collapse threshold.
+    fn hit_rect(&self, layout: SplitLayout, rect: Rectangle, margin: f64) -> Rectangle {
+        let r = if self.collapsed {
+            self.line_rect_at(0.0, self.border, layout, rect)
+        } else {
+            self.line_rect(layout, rect)
+        };
+        match self.orientation {
+            SplitOrientation::Left | SplitOrientation::Right => margin_rect(r, [margin, 0.0]),
+            SplitOrientation::Top | SplitOrientation::Bottom => margin_rect(r, [0.0, margin]),
+        }
+    }
+
+    fn line_rect_at(
+        &self,
+        value: f64,
+        border: f64,
+        layout: SplitLayout,
+        rect: Rectangle
+    ) -> Rectangle {
         match self.orientation {
             SplitOrientation::Left => {
-                [rect[0] + self.value, rect[1] + layout.start,
-                 self.border, rect[3] - layout.start - layout.end]
+                [rect[0] + value, rect[1] + layout.start,
+                 border, rect[3] - layout.start - layout.end]
             }
             SplitOrientation::Right => {
-                [rect[0] + rect[2] - self.value - self.border, rect[1] + layout.start,
-                 self.border, rect[3] - layout.start - layout.end]
+                [rect[0] + rect[2] - value - border, rect[1] + layout.start,
+                 border, rect[3] - layout.start - layout.end]
             }
             SplitOrientation::Top => {
-                [rect[0] + layout.start, rect[1] + self.value,
-                 rect[2] - layout.start - layout.end, self.border]
+                [rect[0] + layout.start, rect[1] + value,
+                 rect[2] - layout.start - layout.end, border]
             }
             SplitOrientation::Bottom => {
-                [rect[0] + layout.start, rect[1] + rect[3] - self.value - self.border,
-                 rect[2] - layout.start - layout.end, self.border]
+                [rect[0] + layout.start, rect[1] + rect[3] - value - border,
+                 rect[2] - layout.start - layout.end, border]
             }
         }
     }
@@ -412,6 +1834,7 @@ impl SplitController {
 
 /// Stores split layout.
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SplitLayout {
     /// The start of split from edge of parent panel.
     pub start: f64,
@@ -434,6 +1857,7 @@ impl SplitLayoutPurpose {
 
 /// Orients split from an edge of parent panel.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SplitOrientation {
     /// Splits from left edge of parent panel.
     Left,
@@ -445,8 +1869,231 @@ pub enum SplitOrientation {
     Bottom,
 }
 
+/// Identifies a side of split layout.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Side {
+    /// The left side.
+    Left,
+    /// The right side.
+    Right,
+    /// The top side.
+    Top,
+    /// The bottom side.
+    Bottom,
+}
+
+impl From<Side> for SplitOrientation {
+    fn from(side: Side) -> SplitOrientation {
+        match side {
+            Side::Left => SplitOrientation::Left,
+            Side::Right => SplitOrientation::Right,
+            Side::Top => SplitOrientation::Top,
+            Side::Bottom => SplitOrientation::Bottom,
+        }
+    }
+}
+
+impl From<SplitOrientation> for Side {
+    fn from(orientation: SplitOrientation) -> Side {
+        match orientation {
+            SplitOrientation::Left => Side::Left,
+            SplitOrientation::Right => Side::Right,
+            SplitOrientation::Top => Side::Top,
+            SplitOrientation::Bottom => Side::Bottom,
+        }
+    }
+}
+
+/// Stores a value for each side of split layout `[left, right, top, bottom]`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Sides<T>(pub [T; 4]);
+
+impl<T> Deref for Sides<T> {
+    type Target = [T; 4];
+    fn deref(&self) -> &[T; 4] {&self.0}
+}
+
+impl<T> DerefMut for Sides<T> {
+    fn deref_mut(&mut self) -> &mut [T; 4] {&mut self.0}
+}
+
+impl<T> Index<Side> for Sides<T> {
+    type Output = T;
+    fn index(&self, side: Side) -> &T {&self.0[side as usize]}
+}
+
+impl<T> IndexMut<Side> for Sides<T> {
+    fn index_mut(&mut self, side: Side) -> &mut T {&mut self.0[side as usize]}
+}
+
+impl<T> Index<usize> for Sides<T> {
+    type Output = T;
+    fn index(&self, i: usize) -> &T {&self.0[i]}
+}
+
+impl<T> IndexMut<usize> for Sides<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {&mut self.0[i]}
+}
+
+/// Identifies a panel of split layout.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PanelId {
+    /// The panel at left edge.
+    Left,
+    /// The panel at right edge.
+    Right,
+    /// The panel at top edge.
+    Top,
+    /// The panel at bottom edge.
+    Bottom,
+    /// The panel in the center.
+    Center,
+}
+
+impl From<Side> for PanelId {
+    fn from(side: Side) -> PanelId {
+        match side {
+            Side::Left => PanelId::Left,
+            Side::Right => PanelId::Right,
+            Side::Top => PanelId::Top,
+            Side::Bottom => PanelId::Bottom,
+        }
+    }
+}
+
+/// Stores a value for each panel of split layout `[left, right, top, bottom, center]`.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Panels<T>(pub [T; 5]);
+
+impl<T> Deref for Panels<T> {
+    type Target = [T; 5];
+    fn deref(&self) -> &[T; 5] {&self.0}
+}
+
+impl<T> DerefMut for Panels<T> {
+    fn deref_mut(&mut self) -> &mut [T; 5] {&mut self.0}
+}
+
+impl<T> Index<PanelId> for Panels<T> {
+    type Output = T;
+    fn index(&self, panel: PanelId) -> &T {&self.0[panel as usize]}
+}
+
+impl<T> IndexMut<PanelId> for Panels<T> {
+    fn index_mut(&mut self, panel: PanelId) -> &mut T {&mut self.0[panel as usize]}
+}
+
+impl<T> Index<usize> for Panels<T> {
+    type Output = T;
+    fn index(&self, i: usize) -> &T {&self.0[i]}
+}
+
+impl<T> IndexMut<usize> for Panels<T> {
+    fn index_mut(&mut self, i: usize) -> &mut T {&mut self.0[i]}
+}
+
+/// Sizes split relative to parent panel.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SplitSizing {
+    /// The value is an absolute distance from edge of parent panel.
+    Absolute,
+    /// The value is a fraction of the size of parent panel.
+    ///
+    /// The fraction is updated when the user drags the split.
+    Fraction(f64),
+}
+
+/// A value that a split snaps to while dragging.
+#[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SnapPoint {
+    /// An absolute distance from edge of parent panel.
+    ///
+    /// This can be updated to align with other splits.
+    Value(f64),
+    /// A fraction of the size of parent panel.
+    Fraction(f64),
+    /// The default value of split.
+    Default,
+    /// The value of split at another side of a layout, for panels of equal size.
+    ///
+    /// This is resolved by `SplitLayoutController`, and ignored for splits outside a layout.
+    /// Collapsed splits are ignored.
+    Split(Side),
+}
+
+/// Decides what happens when double clicking a split.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DoubleClickAction {
+    /// Moves the split to its default value.
+    ResetToDefault,
+    /// Collapses the split, or expands it when collapsed.
+    ToggleCollapse,
+    /// Moves the split to the size of content, when known.
+    FitToContent,
+}
+
+/// Identifies a corner where two perpendicular splits meet.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Corner {
+    /// Where the left split meets the top split.
+    TopLeft,
+    /// Where the right split meets the top split.
+    TopRight,
+    /// Where the left split meets the bottom split.
+    BottomLeft,
+    /// Where the right split meets the bottom split.
+    BottomRight,
+}
+
+impl Corner {
+    /// Gets the splits `[left/right, top/bottom]` meeting at the corner.
+    pub fn splits(self) -> [Side; 2] {
+        match self {
+            Corner::TopLeft => [Side::Left, Side::Top],
+            Corner::TopRight => [Side::Right, Side::Top],
+            Corner::BottomLeft => [Side::Left, Side::Bottom],
+            Corner::BottomRight => [Side::Right, Side::Bottom],
+        }
+    }
+}
+
+/// Decides which panel extends into a corner of split layout.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CornerOwner {
+    /// The top or bottom panel spans the corner.
+    TopBottom,
+    /// The left or right panel spans the corner.
+    LeftRight,
+}
+
+/// The corners of split layout in the order `[top_left, top_right, bottom_left, bottom_right]`.
+const CORNERS: [Corner; 4] = [
+    Corner::TopLeft, Corner::TopRight,
+    Corner::BottomLeft, Corner::BottomRight
+];
+
+/// Decides how splits shrink when parent panel is smaller than the minimum size.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShrinkPolicy {
+    /// Panels keep their size, such that the layout overflows parent panel.
+    Overflow,
+    /// Panels shrink proportionally down to their minimum value.
+    Proportional,
+    /// Panels shrink one at a time in the given order, down to their minimum value.
+    Priority([Side; 4]),
+}
+
 /// Gets the state of split.
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SplitState {
     /// Split is inactive.
     Inactive,
@@ -456,4 +2103,545 @@ pub enum SplitState {
     Drag,
     /// User is dragging, but split is not following.
     DragNotFollowing,
+    /// User is dragging beyond the collapse threshold, split collapses on release.
+    CollapsePending,
+    /// Split has keyboard focus.
+    Focused,
+    /// User is dragging two splits at once at a corner.
+    CornerDrag,
+    /// Split is locked and can not be moved by the user.
+    Locked,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use input::{ButtonArgs, ButtonState, Event, Input, Loop, Motion, UpdateArgs};
+
+    const RECT: Rectangle = [0.0, 0.0, 1000.0, 600.0];
+    const IDENTITY: Matrix2d = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+    fn shrink_layout(policy: ShrinkPolicy) -> SplitLayoutController {
+        let settings = SplitLayoutSettings::new(5.0, 10.0)
+            .left(300.0, 100.0)
+            .right(300.0, 50.0)
+            .shrink_policy(policy);
+        SplitLayoutController::new(&settings)
+    }
+
+    fn cursor(pos: Vec2d) -> Event {
+        Event::Input(Input::Move(Motion::MouseCursor(pos)), None)
+    }
+
+    fn button(button: Button, state: ButtonState) -> Event {
+        Event::Input(Input::Button(ButtonArgs {state, button, scancode: None}), None)
+    }
+
+    fn click(layout: &mut SplitLayoutController, pos: Vec2d) {
+        layout.event(RECT, IDENTITY, &cursor(pos));
+        let left = Button::Mouse(MouseButton::Left);
+        layout.event(RECT, IDENTITY, &button(left, ButtonState::Press));
+        layout.event(RECT, IDENTITY, &button(left, ButtonState::Release));
+    }
+
+    fn widths(layout: &SplitLayoutController, rect: Rectangle) -> [f64; 2] {
+        let panels = layout.panel_rectangles(rect);
+        [panels[0][2], panels[1][2]]
+    }
+
+    #[test]
+    fn set_value_updates_fraction() {
+        let mut split = SplitController::new(250.0, 0.0, 2.0, SplitOrientation::Left);
+        split.sizing = SplitSizing::Fraction(0.25);
+        split.resize(f64::INFINITY, RECT);
+        split.set_value(400.0);
+        split.resize(f64::INFINITY, RECT);
+        assert_eq!(split.value, 400.0);
+        split.resize(f64::INFINITY, [0.0, 0.0, 2000.0, 600.0]);
+        assert_eq!(split.value, 800.0);
+    }
+
+    #[test]
+    fn fraction_sizes_split_before_events() {
+        let settings = SplitLayoutSettings::new(5.0, 10.0).left(100.0, 50.0).left_fraction(0.25);
+        let mut layout = SplitLayoutController::new(&settings);
+        assert_eq!(layout.panel_rectangles(RECT)[PanelId::Left][2], 250.0);
+        layout.event(RECT, IDENTITY, &cursor([500.0, 300.0]));
+        assert_eq!(layout.panel_rectangles(RECT)[PanelId::Left][2], 250.0);
+    }
+
+    #[test]
+    fn move_to_updates_fraction() {
+        let mut split = SplitController::new(250.0, 0.0, 2.0, SplitOrientation::Top);
+        split.sizing = SplitSizing::Fraction(0.25);
+        split.move_to(300.0, f64::INFINITY, RECT);
+        assert_eq!(split.sizing, SplitSizing::Fraction(0.5));
+    }
+
+    #[test]
+    fn shrink_overflow() {
+        let layout = shrink_layout(ShrinkPolicy::Overflow);
+        assert_eq!(widths(&layout, [0.0, 0.0, 400.0, 600.0]), [300.0, 300.0]);
+    }
+
+    #[test]
+    fn shrink_proportional() {
+        let layout = shrink_layout(ShrinkPolicy::Proportional);
+        // Excess is 300 + 300 + 2 * 5 + 1 - 400 = 211, slack is 200 and 250.
+        let [left, right] = widths(&layout, [0.0, 0.0, 400.0, 600.0]);
+        assert!((left - (300.0 - 211.0 * 200.0 / 450.0)).abs() < 1e-9);
+        assert!((right - (300.0 - 211.0 * 250.0 / 450.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn shrink_priority() {
+        use Side::*;
+
+        let layout = shrink_layout(ShrinkPolicy::Priority([Right, Left, Top, Bottom]));
+        assert_eq!(widths(&layout, [0.0, 0.0, 400.0, 600.0]), [300.0, 89.0]);
+    }
+
+    #[test]
+    fn shrink_does_not_change_values() {
+        let mut layout = shrink_layout(ShrinkPolicy::Proportional);
+        let small = [0.0, 0.0, 400.0, 600.0];
+        let events = layout.event(small, IDENTITY, &cursor([200.0, 300.0]));
+        assert!(events.iter().all(|e| !matches!(*e, SplitLayoutEvent::ValueChanged {..})));
+        assert_eq!(layout.left.value, 300.0);
+        assert_eq!(layout.right.value, 300.0);
+        layout.event(RECT, IDENTITY, &cursor([200.0, 300.0]));
+        assert_eq!(widths(&layout, RECT), [300.0, 300.0]);
+    }
+
+    #[test]
+    fn shrink_max_value_uses_shrunk_values() {
+        let mut layout = shrink_layout(ShrinkPolicy::Priority([
+            Side::Right, Side::Left,
+            Side::Top, Side::Bottom,
+        ]));
+        layout.set_focus(Some(Side::Left));
+        let left = button(Button::Keyboard(Key::Left), ButtonState::Press);
+        // The right split is shrunk to 50, which leaves 300 - 50 - 2 * 5 - 1 for the left split.
+        layout.event([0.0, 0.0, 300.0, 600.0], IDENTITY, &left);
+        assert_eq!(layout.left.value, 238.0);
+        assert_eq!(layout.right.value, 300.0);
+    }
+
+    #[test]
+    fn resize_updates_touch_scale_of_locked_splits() {
+        use input::ResizeArgs;
+
+        let mut layout = shrink_layout(ShrinkPolicy::Overflow);
+        layout.set_locked(Side::Left, true);
+        let resize = Event::Input(Input::Resize(ResizeArgs {
+            window_size: [800.0, 600.0],
+            draw_size: [800, 600],
+        }), None);
+        layout.event(RECT, IDENTITY, &resize);
+        assert_eq!(layout.left.touch_scale, [800.0, 600.0]);
+    }
+
+    #[test]
+    fn restore_stops_corner_drag() {
+        let mut layout = shrink_layout(ShrinkPolicy::Overflow);
+        let snapshot = layout.snapshot();
+        layout.corner_hover = Some(Corner::TopLeft);
+        layout.corner_drag = Some(Corner::TopLeft);
+        layout.restore(&snapshot);
+        assert!(layout.states().iter().all(|&state| state != SplitState::CornerDrag));
+        assert_eq!(layout.corner_states(), [SplitState::Inactive; 4]);
+    }
+
+    #[test]
+    fn double_click_time_advances_while_locked() {
+        let settings = SplitLayoutSettings::new(5.0, 10.0)
+            .left(300.0, 100.0)
+            .double_click(DoubleClickAction::ToggleCollapse);
+        let mut layout = SplitLayoutController::new(&settings);
+        click(&mut layout, [302.0, 300.0]);
+        layout.set_locked(Side::Left, true);
+        layout.event(RECT, IDENTITY, &Event::Loop(Loop::Update(UpdateArgs {dt: 1.0})));
+        layout.set_locked(Side::Left, false);
+        click(&mut layout, [302.0, 300.0]);
+        assert!(!layout.left.is_collapsed());
+        click(&mut layout, [302.0, 300.0]);
+        assert!(layout.left.is_collapsed());
+    }
+
+    #[test]
+    fn collapsed_split_without_threshold_can_not_be_dragged() {
+        let settings = SplitLayoutSettings::new(5.0, 10.0)
+            .left(300.0, 100.0)
+            .double_click(DoubleClickAction::ToggleCollapse);
+        let mut layout = SplitLayoutController::new(&settings);
+        layout.left.collapse();
+        layout.event(RECT, IDENTITY, &cursor([2.0, 300.0]));
+        let left = Button::Mouse(MouseButton::Left);
+        layout.event(RECT, IDENTITY, &button(left, ButtonState::Press));
+        layout.event(RECT, IDENTITY, &cursor([200.0, 300.0]));
+        assert!(!layout.left.is_dragging());
+        layout.event(RECT, IDENTITY, &button(left, ButtonState::Release));
+        assert!(layout.left.is_collapsed());
+    }
+
+    #[test]
+    fn drag_keeps_grab_offset() {
+        let mut split = SplitController::new(200.0, 50.0, 20.0, SplitOrientation::Left);
+        drag_split(&mut split, [215.0, 300.0], [265.0, 300.0]);
+        assert_eq!(split.value, 250.0);
+        split_event(&mut split, &cursor([203.0, 300.0]));
+        assert_eq!(split.value, 188.0);
+    }
+
+    #[test]
+    fn collapse_by_drag_beyond_threshold() {
+        let mut split = SplitController::new(200.0, 50.0, 4.0, SplitOrientation::Left);
+        split.collapse_threshold = Some(20.0);
+        let left = Button::Mouse(MouseButton::Left);
+        drag_split(&mut split, [202.0, 300.0], [42.0, 300.0]);
+        assert_eq!(split.value, 50.0);
+        assert!(split.state() != SplitState::CollapsePending);
+        split_event(&mut split, &cursor([22.0, 300.0]));
+        assert_eq!(split.state(), SplitState::CollapsePending);
+        assert!(!split.is_collapsed());
+        split_event(&mut split, &button(left, ButtonState::Release));
+        assert!(split.is_collapsed());
+        assert_eq!(split.visible_value(), 0.0);
+        split.expand();
+        assert_eq!(split.value, 200.0);
+    }
+
+    #[test]
+    fn drag_collapsed_split_open() {
+        let mut split = SplitController::new(200.0, 50.0, 4.0, SplitOrientation::Left);
+        split.collapse_threshold = Some(20.0);
+        split.hit_margin = 4.0;
+        split.collapse();
+        drag_split(&mut split, [1.0, 300.0], [20.0, 300.0]);
+        assert!(split.is_collapsed());
+        split_event(&mut split, &cursor([151.0, 300.0]));
+        assert!(!split.is_collapsed());
+        assert_eq!(split.value, 150.0);
+    }
+
+    #[test]
+    fn index_by_side_and_panel() {
+        let settings = SplitLayoutSettings::new(5.0, 10.0).left(300.0, 100.0).top(100.0, 50.0);
+        let layout = SplitLayoutController::new(&settings);
+        let rectangles = layout.rectangles(RECT);
+        assert_eq!(rectangles[Side::Left], rectangles[0]);
+        assert_eq!(rectangles[Side::Top], rectangles[2]);
+        assert_eq!(rectangles[Side::Left][0], 300.0);
+        let panels = layout.panel_rectangles(RECT);
+        assert_eq!(panels[PanelId::from(Side::Left)], layout.panel_rect(PanelId::Left, RECT));
+        assert_eq!(panels[PanelId::Center][0], 305.0);
+        assert_eq!(layout.split(Side::Top).value, 100.0);
+        let sides: Vec<Side> = layout.sides(RECT).map(|(side, _, _)| side).collect();
+        assert_eq!(sides, vec![Side::Left, Side::Right, Side::Top, Side::Bottom]);
+    }
+
+    fn split_event(split: &mut SplitController, e: &Event) {
+        split.event(SplitLayout {start: 0.0, end: 0.0}, f64::INFINITY, RECT, IDENTITY, e);
+    }
+
+    fn drag_split(split: &mut SplitController, from: Vec2d, to: Vec2d) {
+        split_event(split, &cursor(from));
+        split_event(split, &button(Button::Mouse(MouseButton::Left), ButtonState::Press));
+        split_event(split, &cursor(to));
+    }
+
+    #[test]
+    fn snap_to_value_fraction_and_default() {
+        let mut split = SplitController::new(100.0, 50.0, 4.0, SplitOrientation::Left);
+        split.default_value = 200.0;
+        split.snap_points = vec![
+            SnapPoint::Value(300.0), SnapPoint::Fraction(0.5), SnapPoint::Default
+        ];
+        drag_split(&mut split, [102.0, 10.0], [296.0, 10.0]);
+        assert_eq!((split.value, split.snap_target()), (300.0, Some(300.0)));
+        split_event(&mut split, &cursor([508.0, 10.0]));
+        assert_eq!((split.value, split.snap_target()), (500.0, Some(500.0)));
+        split_event(&mut split, &cursor([197.0, 10.0]));
+        assert_eq!((split.value, split.snap_target()), (200.0, Some(200.0)));
+        split_event(&mut split, &cursor([252.0, 10.0]));
+        assert_eq!((split.value, split.snap_target()), (250.0, None));
+    }
+
+    #[test]
+    fn snap_within_limits() {
+        let mut split = SplitController::new(100.0, 50.0, 4.0, SplitOrientation::Left);
+        split.max_value = 298.0;
+        split.snap_points = vec![SnapPoint::Value(300.0), SnapPoint::Value(45.0)];
+        drag_split(&mut split, [102.0, 10.0], [298.0, 10.0]);
+        assert_eq!((split.value, split.snap_target()), (296.0, None));
+        split_event(&mut split, &cursor([52.0, 10.0]));
+        assert_eq!((split.value, split.snap_target()), (50.0, None));
+    }
+
+    #[test]
+    fn snap_to_split_in_layout() {
+        let settings = SplitLayoutSettings::new(4.0, 10.0)
+            .left(100.0, 50.0)
+            .right(200.0, 50.0);
+        let mut layout = SplitLayoutController::new(&settings);
+        layout.left.snap_points = vec![
+            SnapPoint::Split(Side::Right), SnapPoint::Split(Side::Left)
+        ];
+        let left = Button::Mouse(MouseButton::Left);
+        layout.event(RECT, IDENTITY, &cursor([102.0, 300.0]));
+        layout.event(RECT, IDENTITY, &button(left, ButtonState::Press));
+        layout.event(RECT, IDENTITY, &cursor([196.0, 300.0]));
+        assert_eq!(layout.left.value, 200.0);
+        assert_eq!(layout.snap_targets()[Side::Left], Some(200.0));
+
+        // Standalone splits ignore snapping to other splits.
+        let mut split = SplitController::new(100.0, 50.0, 4.0, SplitOrientation::Left);
+        split.snap_points = vec![SnapPoint::Split(Side::Right)];
+        drag_split(&mut split, [102.0, 10.0], [196.0, 10.0]);
+        assert_eq!((split.value, split.snap_target()), (194.0, None));
+    }
+
+    #[test]
+    fn clamp_quantizes_to_step() {
+        let mut split = SplitController::new(100.0, 50.0, 4.0, SplitOrientation::Left);
+        split.max_value = 300.0;
+        split.step = Some(16.0);
+        assert_eq!(split.clamp(100.0, f64::INFINITY), 96.0);
+        assert_eq!(split.clamp(40.0, f64::INFINITY), 64.0);
+        assert_eq!(split.clamp(310.0, f64::INFINITY), 288.0);
+        assert_eq!(split.clamp(310.0, 200.0), 192.0);
+        // Without a multiple of step between minimum and maximum value, the value is kept.
+        split.max_value = 60.0;
+        assert_eq!(split.clamp(55.0, f64::INFINITY), 55.0);
+    }
+
+    #[test]
+    fn quantize_on_new_restore_and_resize() {
+        let settings = SplitLayoutSettings::new(4.0, 10.0)
+            .left(100.0, 50.0)
+            .left_step(16.0);
+        let mut layout = SplitLayoutController::new(&settings);
+        assert_eq!(layout.left.value, 96.0);
+
+        let mut snapshot = layout.snapshot();
+        snapshot.left.value = 140.0;
+        layout.restore(&snapshot);
+        assert_eq!(layout.left.value, 144.0);
+
+        let mut split = SplitController::new(100.0, 50.0, 4.0, SplitOrientation::Left);
+        split.max_value = 300.0;
+        split.step = Some(16.0);
+        split.resize(f64::INFINITY, RECT);
+        assert_eq!(split.value, 96.0);
+        split.value = 400.0;
+        split.resize(f64::INFINITY, RECT);
+        assert_eq!(split.value, 288.0);
+    }
+
+    #[test]
+    fn shrink_keeps_step_of_stored_value() {
+        let settings = SplitLayoutSettings::new(4.0, 10.0)
+            .left(320.0, 50.0)
+            .left_step(16.0)
+            .right(300.0, 50.0)
+            .shrink_policy(ShrinkPolicy::Proportional);
+        let mut layout = SplitLayoutController::new(&settings);
+        let small = [0.0, 0.0, 500.0, 600.0];
+        layout.event(small, IDENTITY, &cursor([10.0, 10.0]));
+        assert_eq!(layout.left.value, 320.0);
+
+        let x = layout.rectangles(small)[Side::Left][0];
+        let left = Button::Mouse(MouseButton::Left);
+        layout.event(small, IDENTITY, &cursor([x + 2.0, 300.0]));
+        layout.event(small, IDENTITY, &button(left, ButtonState::Press));
+        layout.event(small, IDENTITY, &cursor([x - 30.0, 300.0]));
+        layout.event(small, IDENTITY, &button(left, ButtonState::Release));
+        assert_eq!(layout.left.value % 16.0, 0.0);
+        assert!((layout.left.value - (x - 30.0)).abs() <= 8.0);
+    }
+
+    #[test]
+    fn drag_shrunk_split_follows_cursor() {
+        let mut layout = shrink_layout(ShrinkPolicy::Proportional);
+        let small = [0.0, 0.0, 400.0, 600.0];
+        layout.event(small, IDENTITY, &cursor([10.0, 300.0]));
+        let x = layout.rectangles(small)[Side::Left][0];
+        let left = Button::Mouse(MouseButton::Left);
+        layout.event(small, IDENTITY, &cursor([x + 2.0, 300.0]));
+        layout.event(small, IDENTITY, &button(left, ButtonState::Press));
+        assert_eq!(layout.rectangles(small)[Side::Left][0], x);
+        for &dx in &[-10.0, -20.0, -30.0, -20.0, 10.0] {
+            layout.event(small, IDENTITY, &cursor([x + 2.0 + dx, 300.0]));
+            assert!((layout.rectangles(small)[Side::Left][0] - (x + dx)).abs() < 1e-9);
+        }
+
+        // Canceling restores the stored value, which is shrunk again.
+        layout.event(small, IDENTITY, &button(Button::Keyboard(Key::Escape), ButtonState::Press));
+        assert_eq!(layout.left.value, 300.0);
+        assert!((layout.rectangles(small)[Side::Left][0] - x).abs() < 1e-9);
+    }
+
+    #[test]
+    fn collapsed_side_has_no_size() {
+        let settings = SplitLayoutSettings::new(5.0, 10.0).left(300.0, 100.0);
+        let mut layout = SplitLayoutController::new(&settings);
+        let min_size = layout.min_size();
+        layout.left.collapse();
+        layout.event(RECT, IDENTITY, &cursor([500.0, 300.0]));
+        assert_eq!(layout.rectangles(RECT)[Side::Left][2], 0.0);
+        let panels = layout.panel_rectangles(RECT);
+        assert_eq!(panels[PanelId::Left][2], 0.0);
+        assert_eq!(panels[PanelId::Center][0], 0.0);
+        assert_eq!(layout.min_size(), [min_size[0] - 305.0, min_size[1]]);
+
+        layout.left.expand();
+        assert_eq!(layout.left.value, 300.0);
+        assert_eq!(layout.panel_rectangles(RECT)[PanelId::Center][0], 305.0);
+    }
+
+    #[test]
+    fn max_value_limits_drag_set_value_and_resize() {
+        let settings = SplitLayoutSettings::new(5.0, 10.0).left(300.0, 100.0).left_max(400.0);
+        let mut layout = SplitLayoutController::new(&settings);
+        let left = Button::Mouse(MouseButton::Left);
+        layout.event(RECT, IDENTITY, &cursor([302.0, 300.0]));
+        layout.event(RECT, IDENTITY, &button(left, ButtonState::Press));
+        layout.event(RECT, IDENTITY, &cursor([702.0, 300.0]));
+        layout.event(RECT, IDENTITY, &button(left, ButtonState::Release));
+        assert_eq!(layout.left.value, 400.0);
+
+        layout.left.set_value(500.0);
+        assert_eq!(layout.left.value, 400.0);
+
+        layout.left.max_value = 350.0;
+        layout.event(RECT, IDENTITY, &cursor([500.0, 300.0]));
+        assert_eq!(layout.left.value, 350.0);
+    }
+
+    #[test]
+    fn drag_emits_layout_events() {
+        use SplitLayoutEvent::*;
+
+        let settings = SplitLayoutSettings::new(5.0, 10.0).left(300.0, 100.0);
+        let mut layout = SplitLayoutController::new(&settings);
+        let left = Button::Mouse(MouseButton::Left);
+        assert_eq!(layout.event(RECT, IDENTITY, &cursor([302.0, 300.0])),
+                   vec![HoverChanged {side: Side::Left, hover: true}]);
+        assert_eq!(layout.event(RECT, IDENTITY, &button(left, ButtonState::Press)),
+                   vec![DragStarted(Side::Left)]);
+        assert_eq!(layout.event(RECT, IDENTITY, &cursor([352.0, 300.0])),
+                   vec![ValueChanged {side: Side::Left, old: 300.0, new: 350.0}]);
+        assert_eq!(layout.event(RECT, IDENTITY, &button(left, ButtonState::Release)),
+                   vec![DragEnded(Side::Left)]);
+        assert_eq!(layout.event(RECT, IDENTITY, &cursor([500.0, 300.0])),
+                   vec![HoverChanged {side: Side::Left, hover: false}]);
+    }
+
+    #[test]
+    fn hit_margin_widens_hover_area() {
+        let settings = SplitLayoutSettings::new(1.0, 10.0).left(300.0, 100.0).hit_margin(4.0);
+        let mut layout = SplitLayoutController::new(&settings);
+        layout.event(RECT, IDENTITY, &cursor([304.0, 300.0]));
+        assert_eq!(layout.states()[Side::Left], SplitState::Hover);
+        assert_eq!(layout.rectangles(RECT)[Side::Left][2], 1.0);
+        layout.event(RECT, IDENTITY, &cursor([306.0, 300.0]));
+        assert_eq!(layout.states()[Side::Left], SplitState::Inactive);
+    }
+
+    #[test]
+    fn corner_drag_moves_both_splits() {
+        let settings = SplitLayoutSettings::new(5.0, 10.0).left(300.0, 100.0).top(100.0, 50.0);
+        let mut layout = SplitLayoutController::new(&settings);
+        assert_eq!(layout.corner_rectangles(RECT)[0], [300.0, 100.0, 5.0, 5.0]);
+        let left = Button::Mouse(MouseButton::Left);
+        layout.event(RECT, IDENTITY, &cursor([302.0, 102.0]));
+        assert_eq!(layout.corner_states()[0], SplitState::Hover);
+        layout.event(RECT, IDENTITY, &button(left, ButtonState::Press));
+        assert_eq!(layout.corner_states()[0], SplitState::CornerDrag);
+        assert_eq!(layout.states()[Side::Left], SplitState::CornerDrag);
+        assert_eq!(layout.states()[Side::Top], SplitState::CornerDrag);
+        layout.event(RECT, IDENTITY, &cursor([352.0, 152.0]));
+        assert_eq!((layout.left.value, layout.top.value), (350.0, 150.0));
+        layout.event(RECT, IDENTITY, &button(left, ButtonState::Release));
+        assert_eq!(layout.corner_states()[0], SplitState::Hover);
+        assert!(layout.states()[Side::Left] != SplitState::CornerDrag);
+    }
+
+    #[test]
+    fn corner_owner_extends_side_panel() {
+        let settings = SplitLayoutSettings::new(5.0, 10.0)
+            .left(300.0, 100.0)
+            .top(100.0, 50.0)
+            .corner_owner(Corner::TopLeft, CornerOwner::LeftRight);
+        let layout = SplitLayoutController::new(&settings);
+        let panels = layout.panel_rectangles(RECT);
+        assert_eq!(panels[PanelId::Left], [0.0, 0.0, 300.0, 585.0]);
+        assert_eq!(panels[PanelId::Top], [305.0, 0.0, 695.0, 100.0]);
+        let rectangles = layout.rectangles(RECT);
+        assert_eq!(rectangles[Side::Left], [300.0, 0.0, 5.0, 585.0]);
+        assert_eq!(rectangles[Side::Top], [305.0, 100.0, 695.0, 5.0]);
+    }
+
+    #[test]
+    fn drag_threshold_delays_drag() {
+        let settings = SplitLayoutSettings::new(5.0, 10.0).left(300.0, 100.0).drag_threshold(4.0);
+        let mut layout = SplitLayoutController::new(&settings);
+        let left = Button::Mouse(MouseButton::Left);
+        layout.event(RECT, IDENTITY, &cursor([302.0, 300.0]));
+        layout.event(RECT, IDENTITY, &button(left, ButtonState::Press));
+        assert!(layout.left.is_drag_pending());
+        layout.event(RECT, IDENTITY, &cursor([305.0, 300.0]));
+        assert!(layout.left.is_drag_pending() && !layout.left.is_dragging());
+        assert_eq!(layout.left.value, 300.0);
+        layout.event(RECT, IDENTITY, &cursor([312.0, 300.0]));
+        assert!(layout.left.is_dragging());
+        assert_eq!(layout.left.value, 310.0);
+    }
+
+    #[test]
+    fn escape_and_cancel_button_cancel_drag() {
+        use SplitLayoutEvent::*;
+
+        let settings = SplitLayoutSettings::new(5.0, 10.0)
+            .left(300.0, 100.0)
+            .cancel_button(Button::Mouse(MouseButton::Right));
+        let mut layout = SplitLayoutController::new(&settings);
+        let left = Button::Mouse(MouseButton::Left);
+        let cancel_buttons = [Button::Keyboard(Key::Escape), Button::Mouse(MouseButton::Right)];
+        for &cancel in &cancel_buttons {
+            layout.event(RECT, IDENTITY, &cursor([302.0, 300.0]));
+            layout.event(RECT, IDENTITY, &button(left, ButtonState::Press));
+            layout.event(RECT, IDENTITY, &cursor([352.0, 300.0]));
+            assert_eq!(layout.left.value, 350.0);
+            assert_eq!(layout.event(RECT, IDENTITY, &button(cancel, ButtonState::Press)), vec![
+                ValueChanged {side: Side::Left, old: 350.0, new: 300.0},
+                DragEnded(Side::Left),
+            ]);
+            assert_eq!(layout.left.value, 300.0);
+            layout.event(RECT, IDENTITY, &button(left, ButtonState::Release));
+            assert_eq!(layout.left.value, 300.0);
+        }
+    }
+
+    #[test]
+    fn lock_during_drag_notifies_next_event() {
+        use SplitLayoutEvent::*;
+
+        let settings = SplitLayoutSettings::new(5.0, 10.0).left(100.0, 50.0);
+        let mut layout = SplitLayoutController::new(&settings);
+        let left = Button::Mouse(MouseButton::Left);
+        layout.event(RECT, IDENTITY, &cursor([102.0, 300.0]));
+        layout.event(RECT, IDENTITY, &button(left, ButtonState::Press));
+        layout.event(RECT, IDENTITY, &cursor([302.0, 300.0]));
+        assert_eq!(layout.left.value, 300.0);
+        layout.lock_all();
+        assert_eq!(layout.left.value, 100.0);
+        assert_eq!(layout.event(RECT, IDENTITY, &cursor([302.0, 300.0])), vec![
+            ValueChanged {side: Side::Left, old: 300.0, new: 100.0},
+            DragEnded(Side::Left),
+            HoverChanged {side: Side::Left, hover: false},
+        ]);
+        assert_eq!(layout.event(RECT, IDENTITY, &cursor([302.0, 300.0])), vec![]);
+    }
 }
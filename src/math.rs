@@ -20,6 +20,12 @@ pub fn is_inside(pos: Vec2d, rect: Rectangle) -> bool {
     pos[1] < rect[1] + rect[3]
 }
 
+/// Expands rectangle by a margin `[x, y]` on each side, for hit testing.
+pub fn margin_rect(rect: Rectangle, margin: Vec2d) -> Rectangle {
+    [rect[0] - margin[0], rect[1] - margin[1],
+     rect[2] + 2.0 * margin[0], rect[3] + 2.0 * margin[1]]
+}
+
 /// Returns the position inside a transform matrix.
 pub fn inside_pos(outside_pos: Vec2d, transform: Matrix2d) -> Vec2d {
     let inv = inv(transform);
@@ -0,0 +1,369 @@
+//! Split container with multiple panes along one axis.
+
+use input::{Button, GenericEvent, MouseButton};
+
+use super::math::{is_inside, inside_pos, Matrix2d, Rectangle, Vec2d};
+use super::SplitState;
+
+/// Orients panes along an axis.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SplitAxis {
+    /// Panes are placed from left to right.
+    Horizontal,
+    /// Panes are placed from top to bottom.
+    Vertical,
+}
+
+/// Stores information about a pane in a multi split.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct MultiSplitPane {
+    /// The size of pane along the axis.
+    pub size: f64,
+    /// The minimum size of pane.
+    pub min_size: f64,
+    /// The maximum size of pane.
+    pub max_size: f64,
+}
+
+impl MultiSplitPane {
+    /// Creates a new `MultiSplitPane` without maximum size.
+    pub fn new(size: f64, min_size: f64) -> MultiSplitPane {
+        MultiSplitPane {
+            size,
+            min_size,
+            max_size: f64::INFINITY,
+        }
+    }
+
+    /// Sets the maximum size.
+    pub fn max_size(mut self, max_size: f64) -> MultiSplitPane {
+        self.max_size = max_size;
+        self
+    }
+}
+
+/// Stores information about an ordered list of panes along one axis.
+///
+/// There is a split between each pair of neighbour panes.
+/// When a split is dragged, the panes on one side grow and the panes on the other side shrink.
+/// Neighbours are pushed when a pane reaches its minimum or maximum size,
+/// and the split is clamped when no pane can give more room.
+pub struct MultiSplitController {
+    /// The panes.
+    pub panes: Vec<MultiSplitPane>,
+    /// The border width.
+    pub border: f64,
+    /// The axis of panes.
+    pub axis: SplitAxis,
+    // Which split the mouse is hovering over.
+    mouse_hover: Option<usize>,
+    // Which split is dragged.
+    dragging: Option<usize>,
+    // The last position of mouse cursor.
+    cursor: Vec2d,
+    // The offset from split to the position where the user grabbed it.
+    grab_offset: f64,
+}
+
+impl MultiSplitController {
+    /// Creates a new `MultiSplitController`.
+    pub fn new(panes: Vec<MultiSplitPane>, border: f64, axis: SplitAxis) -> MultiSplitController {
+        MultiSplitController {
+            panes,
+            border,
+            axis,
+            mouse_hover: None,
+            dragging: None,
+            cursor: [0.0; 2],
+            grab_offset: 0.0,
+        }
+    }
+
+    /// Gets the split currently being dragged by the user.
+    pub fn dragging(&self) -> Option<usize> {self.dragging}
+
+    /// Handles event.
+    ///
+    /// The pane sizes are fitted to the rectangle before handling the event.
+    pub fn event<E: GenericEvent>(&mut self, rect: Rectangle, transform: Matrix2d, e: &E) {
+        let bounds = self.bounds(rect);
+        let extent = self.extent(bounds);
+        self.fit(extent);
+
+        if let Some(pos) = e.mouse_cursor_args() {
+            let pos = inside_pos(pos, transform);
+            self.cursor = pos;
+            if let Some(i) = self.dragging {
+                let value = self.distance(pos, bounds) - self.grab_offset;
+                let delta = value - split_value(&self.panes, self.border, i);
+                self.move_split(i, delta);
+            }
+            self.mouse_hover = (0..self.splits())
+                .find(|&i| is_inside(pos, self.line_rect(i, bounds)));
+        }
+
+        if let Some(Button::Mouse(MouseButton::Left)) = e.press_args() {
+            if let Some(i) = self.mouse_hover {
+                self.dragging = Some(i);
+                self.grab_offset = self.distance(self.cursor, bounds) -
+                                   split_value(&self.panes, self.border, i);
+            }
+        }
+
+        if let Some(Button::Mouse(MouseButton::Left)) = e.release_args() {
+            self.dragging = None;
+        }
+    }
+
+    /// Moves split by a delta value, pushing or clamping neighbour panes.
+    ///
+    /// Does nothing if there is no such split.
+    pub fn move_split(&mut self, split: usize, delta: f64) {
+        if split >= self.splits() {return};
+
+        let before: Vec<usize> = (0..split + 1).rev().collect();
+        let after: Vec<usize> = (split + 1..self.panes.len()).collect();
+        let (grow, shrink) = if delta > 0.0 {(before, after)} else {(after, before)};
+        let room_grow: f64 = grow.iter().map(|&i| self.panes[i].max_size - self.panes[i].size).sum();
+        let room_shrink: f64 = shrink.iter()
+            .map(|&i| self.panes[i].size - self.panes[i].min_size).sum();
+        let amount = delta.abs().min(room_grow).min(room_shrink);
+        if amount <= 0.0 {return};
+
+        let mut rest = amount;
+        for &i in &grow {
+            let pane = &mut self.panes[i];
+            let d = rest.min(pane.max_size - pane.size).max(0.0);
+            pane.size += d;
+            rest -= d;
+        }
+        let mut rest = amount;
+        for &i in &shrink {
+            let pane = &mut self.panes[i];
+            let d = rest.min(pane.size - pane.min_size).max(0.0);
+            pane.size -= d;
+            rest -= d;
+        }
+    }
+
+    /// Fits the pane sizes to the extent available for panes.
+    ///
+    /// Panes are resized starting with the last one, within their minimum and maximum size.
+    pub fn fit(&mut self, extent: f64) {
+        fit(&mut self.panes, extent);
+    }
+
+    /// Gets the current state of split.
+    pub fn state(&self, split: usize) -> SplitState {
+        match (self.mouse_hover == Some(split), self.dragging == Some(split)) {
+            (false, false) => SplitState::Inactive,
+            (true, false) => SplitState::Hover,
+            (true, true) => SplitState::Drag,
+            (false, true) => SplitState::DragNotFollowing,
+        }
+    }
+
+    /// Returns the split states.
+    pub fn states(&self) -> Vec<SplitState> {
+        (0..self.splits()).map(|i| self.state(i)).collect()
+    }
+
+    /// Gets line rectangle `[x, y, w, h]` of split from rectangle `[x, y, w, h]` of parent panel.
+    pub fn line_rect(&self, split: usize, rect: Rectangle) -> Rectangle {
+        self.line_rect_of(&self.panes, split, rect)
+    }
+
+    /// Computes split rectangles for drawing.
+    ///
+    /// The pane sizes are fitted to the rectangle.
+    pub fn rectangles(&self, rect: Rectangle) -> Vec<Rectangle> {
+        let bounds = self.bounds(rect);
+        let panes = self.fitted(bounds);
+        (0..self.splits()).map(|i| self.line_rect_of(&panes, i, bounds)).collect()
+    }
+
+    /// Computes panel rectangles for layout.
+    ///
+    /// The pane sizes are fitted to the rectangle.
+    pub fn panel_rectangles(&self, rect: Rectangle) -> Vec<Rectangle> {
+        let bounds = self.bounds(rect);
+        let mut offset = 0.0;
+        self.fitted(bounds).into_iter().map(|pane| {
+            let panel = match self.axis {
+                SplitAxis::Horizontal => [bounds[0] + offset, bounds[1], pane.size, bounds[3]],
+                SplitAxis::Vertical => [bounds[0], bounds[1] + offset, bounds[2], pane.size],
+            };
+            offset += pane.size + self.border;
+            panel
+        }).collect()
+    }
+
+    /// Computes the minimum size along the axis using the minimum size of panes.
+    pub fn min_size(&self) -> [f64; 2] {
+        let min: f64 = self.panes.iter().map(|pane| pane.min_size).sum::<f64>() +
+                       self.splits() as f64 * self.border;
+        match self.axis {
+            SplitAxis::Horizontal => [min, 0.0],
+            SplitAxis::Vertical => [0.0, min],
+        }
+    }
+
+    /// Computes the bounds from window bounds `[x, y, w, h]`.
+    ///
+    /// Does not get less in size than specified by `min_size`.
+    pub fn bounds(&self, rect: Rectangle) -> Rectangle {
+        let min_size = self.min_size();
+        [rect[0], rect[1], rect[2].max(min_size[0]), rect[3].max(min_size[1])]
+    }
+
+    /// Gets the number of splits.
+    fn splits(&self) -> usize {
+        if self.panes.is_empty() {0} else {self.panes.len() - 1}
+    }
+
+    /// Gets the panes with sizes fitted to rectangle `[x, y, w, h]`.
+    fn fitted(&self, rect: Rectangle) -> Vec<MultiSplitPane> {
+        let mut panes = self.panes.clone();
+        fit(&mut panes, self.extent(rect));
+        panes
+    }
+
+    /// Gets line rectangle `[x, y, w, h]` of split between panes.
+    fn line_rect_of(&self, panes: &[MultiSplitPane], split: usize, rect: Rectangle) -> Rectangle {
+        let value = split_value(panes, self.border, split);
+        match self.axis {
+            SplitAxis::Horizontal => [rect[0] + value, rect[1], self.border, rect[3]],
+            SplitAxis::Vertical => [rect[0], rect[1] + value, rect[2], self.border],
+        }
+    }
+
+    /// Gets the distance from start of parent panel to a position along the axis.
+    fn distance(&self, pos: Vec2d, rect: Rectangle) -> f64 {
+        match self.axis {
+            SplitAxis::Horizontal => pos[0] - rect[0],
+            SplitAxis::Vertical => pos[1] - rect[1],
+        }
+    }
+
+    /// Gets the extent available for panes.
+    fn extent(&self, rect: Rectangle) -> f64 {
+        let border = self.splits() as f64 * self.border;
+        match self.axis {
+            SplitAxis::Horizontal => rect[2] - border,
+            SplitAxis::Vertical => rect[3] - border,
+        }
+    }
+}
+
+/// Fits pane sizes to an extent, starting with the last pane.
+fn fit(panes: &mut [MultiSplitPane], extent: f64) {
+    let total: f64 = panes.iter().map(|pane| pane.size).sum();
+    let mut rest = extent - total;
+    for pane in panes.iter_mut().rev() {
+        if rest > 0.0 {
+            let d = rest.min(pane.max_size - pane.size).max(0.0);
+            pane.size += d;
+            rest -= d;
+        } else if rest < 0.0 {
+            let d = (-rest).min(pane.size - pane.min_size).max(0.0);
+            pane.size -= d;
+            rest += d;
+        }
+    }
+}
+
+/// Gets the distance from start of parent panel to split.
+fn split_value(panes: &[MultiSplitPane], border: f64, split: usize) -> f64 {
+    panes[..split + 1].iter().map(|pane| pane.size).sum::<f64>() + split as f64 * border
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::{ButtonArgs, ButtonState, Event, Input, Motion};
+
+    const IDENTITY: Matrix2d = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+    fn controller() -> MultiSplitController {
+        let panes = vec![
+            MultiSplitPane::new(100.0, 50.0),
+            MultiSplitPane::new(100.0, 50.0),
+            MultiSplitPane::new(100.0, 50.0).max_size(120.0),
+        ];
+        MultiSplitController::new(panes, 2.0, SplitAxis::Horizontal)
+    }
+
+    fn sizes(multi: &MultiSplitController) -> Vec<f64> {
+        multi.panes.iter().map(|pane| pane.size).collect()
+    }
+
+    fn cursor(pos: Vec2d) -> Event {
+        Event::Input(Input::Move(Motion::MouseCursor(pos)), None)
+    }
+
+    fn button(state: ButtonState) -> Event {
+        let button = Button::Mouse(MouseButton::Left);
+        Event::Input(Input::Button(ButtonArgs {state, button, scancode: None}), None)
+    }
+
+    #[test]
+    fn drag_split_keeps_grab_offset() {
+        let mut multi = controller();
+        let rect = [0.0, 0.0, 304.0, 10.0];
+        multi.event(rect, IDENTITY, &cursor([100.5, 5.0]));
+        multi.event(rect, IDENTITY, &button(ButtonState::Press));
+        assert_eq!(multi.dragging(), Some(0));
+        multi.event(rect, IDENTITY, &cursor([150.5, 5.0]));
+        assert_eq!(sizes(&multi), vec![150.0, 50.0, 100.0]);
+        multi.event(rect, IDENTITY, &button(ButtonState::Release));
+        assert_eq!(multi.dragging(), None);
+        assert_eq!(multi.state(0), SplitState::Hover);
+    }
+
+    #[test]
+    fn move_split_pushes_neighbours() {
+        let mut multi = controller();
+        multi.move_split(0, 80.0);
+        assert_eq!(sizes(&multi), vec![180.0, 50.0, 70.0]);
+    }
+
+    #[test]
+    fn move_split_clamps_to_min_size() {
+        let mut multi = controller();
+        multi.move_split(0, 200.0);
+        assert_eq!(sizes(&multi), vec![200.0, 50.0, 50.0]);
+        multi.move_split(0, -200.0);
+        assert_eq!(sizes(&multi), vec![50.0, 200.0, 50.0]);
+    }
+
+    #[test]
+    fn move_split_clamps_to_max_size() {
+        let mut multi = controller();
+        multi.move_split(1, -80.0);
+        assert_eq!(sizes(&multi), vec![100.0, 80.0, 120.0]);
+    }
+
+    #[test]
+    fn move_split_out_of_range() {
+        let mut multi = controller();
+        multi.move_split(2, 10.0);
+        multi.move_split(5, 10.0);
+        assert_eq!(sizes(&multi), vec![100.0; 3]);
+    }
+
+    #[test]
+    fn panel_rectangles_fit_rect() {
+        let multi = controller();
+        let panels = multi.panel_rectangles([0.0, 0.0, 254.0, 10.0]);
+        assert_eq!(panels, vec![
+            [0.0, 0.0, 100.0, 10.0],
+            [102.0, 0.0, 100.0, 10.0],
+            [204.0, 0.0, 50.0, 10.0],
+        ]);
+        assert_eq!(multi.rectangles([0.0, 0.0, 254.0, 10.0]), vec![
+            [100.0, 0.0, 2.0, 10.0],
+            [202.0, 0.0, 2.0, 10.0],
+        ]);
+    }
+}
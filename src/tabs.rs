@@ -0,0 +1,197 @@
+//! Tabbed panel groups.
+
+use input::{Button, GenericEvent, MouseButton};
+
+use super::math::{is_inside, inside_pos, Matrix2d, Rectangle};
+use super::SplitState;
+
+/// Stores information about an ordered list of panels shown as tabs.
+///
+/// Only the active panel is visible, below a header with one tab per panel.
+/// Clicking a tab activates it, and dragging a tab reorders it among the others.
+pub struct TabGroup {
+    /// The panel ids in tab order.
+    pub panels: Vec<usize>,
+    /// The index of active tab.
+    pub active: usize,
+    /// The height of tab header.
+    pub header_height: f64,
+    /// The maximum width of tabs.
+    pub tab_width: f64,
+    // Which tab the mouse is hovering over.
+    mouse_hover: Option<usize>,
+    // Which tab is dragged.
+    dragging: Option<usize>,
+}
+
+impl TabGroup {
+    /// Creates a new `TabGroup` with the first tab active.
+    ///
+    /// The maximum width of tabs is set to 100.
+    pub fn new(panels: Vec<usize>, header_height: f64) -> TabGroup {
+        TabGroup {
+            panels,
+            active: 0,
+            header_height,
+            tab_width: 100.0,
+            mouse_hover: None,
+            dragging: None,
+        }
+    }
+
+    /// Gets the id of active panel.
+    pub fn active_panel(&self) -> Option<usize> {self.panels.get(self.active).cloned()}
+
+    /// Gets the tab currently being dragged by the user.
+    pub fn dragging(&self) -> Option<usize> {self.dragging}
+
+    /// Gets whether the group contains a panel.
+    pub fn contains(&self, id: usize) -> bool {self.panels.contains(&id)}
+
+    /// Activates the tab of a panel.
+    pub fn activate(&mut self, id: usize) {
+        if let Some(i) = self.panels.iter().position(|&panel| panel == id) {
+            self.active = i;
+        }
+    }
+
+    /// Adds a panel as the last tab and activates it.
+    pub fn push(&mut self, id: usize) {
+        self.panels.push(id);
+        self.active = self.panels.len() - 1;
+    }
+
+    /// Removes the tab of a panel, keeping a neighbour tab active.
+    ///
+    /// Returns `false` if the panel is not found.
+    pub fn remove(&mut self, id: usize) -> bool {
+        match self.panels.iter().position(|&panel| panel == id) {
+            Some(i) => {
+                self.panels.remove(i);
+                if self.active > i || self.active >= self.panels.len() {
+                    self.active = self.active.saturating_sub(1);
+                }
+                self.mouse_hover = None;
+                self.dragging = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Handles event.
+    pub fn event<E: GenericEvent>(&mut self, rect: Rectangle, transform: Matrix2d, e: &E) {
+        if let Some(pos) = e.mouse_cursor_args() {
+            let pos = inside_pos(pos, transform);
+            self.mouse_hover = self.tab_rectangles(rect).into_iter()
+                .position(|tab| is_inside(pos, tab));
+            if let (Some(i), Some(j)) = (self.dragging, self.mouse_hover) {
+                if i != j {
+                    let panel = self.panels.remove(i);
+                    self.panels.insert(j, panel);
+                    self.active = j;
+                    self.dragging = Some(j);
+                }
+            }
+        }
+
+        if let Some(Button::Mouse(MouseButton::Left)) = e.press_args() {
+            if let Some(i) = self.mouse_hover {
+                self.active = i;
+                self.dragging = Some(i);
+            }
+        }
+
+        if let Some(Button::Mouse(MouseButton::Left)) = e.release_args() {
+            self.dragging = None;
+        }
+    }
+
+    /// Gets the current state of tab.
+    pub fn state(&self, tab: usize) -> SplitState {
+        match (self.mouse_hover == Some(tab), self.dragging == Some(tab)) {
+            (false, false) => SplitState::Inactive,
+            (true, false) => SplitState::Hover,
+            (true, true) => SplitState::Drag,
+            (false, true) => SplitState::DragNotFollowing,
+        }
+    }
+
+    /// Returns the tab states.
+    pub fn states(&self) -> Vec<SplitState> {
+        (0..self.panels.len()).map(|i| self.state(i)).collect()
+    }
+
+    /// Computes the header rectangle from rectangle `[x, y, w, h]` of group.
+    pub fn header_rect(&self, rect: Rectangle) -> Rectangle {
+        [rect[0], rect[1], rect[2], self.header_height.min(rect[3])]
+    }
+
+    /// Computes tab rectangles for drawing, from rectangle `[x, y, w, h]` of group.
+    ///
+    /// Tabs share the header width when there is not enough room.
+    pub fn tab_rectangles(&self, rect: Rectangle) -> Vec<Rectangle> {
+        let header = self.header_rect(rect);
+        let n = self.panels.len();
+        if n == 0 {return vec![]};
+
+        let width = self.tab_width.min(header[2] / n as f64);
+        (0..n).map(|i| [header[0] + i as f64 * width, header[1], width, header[3]]).collect()
+    }
+
+    /// Computes the content rectangle of active panel, from rectangle `[x, y, w, h]` of group.
+    pub fn content_rect(&self, rect: Rectangle) -> Rectangle {
+        let header = self.header_height.min(rect[3]);
+        [rect[0], rect[1] + header, rect[2], rect[3] - header]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use input::{ButtonArgs, ButtonState, Event, Input, Motion};
+
+    const RECT: Rectangle = [0.0, 0.0, 400.0, 300.0];
+    const IDENTITY: Matrix2d = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+
+    fn cursor(pos: [f64; 2]) -> Event {
+        Event::Input(Input::Move(Motion::MouseCursor(pos)), None)
+    }
+
+    fn button(state: ButtonState) -> Event {
+        let button = Button::Mouse(MouseButton::Left);
+        Event::Input(Input::Button(ButtonArgs {state, button, scancode: None}), None)
+    }
+
+    #[test]
+    fn click_activates_tab() {
+        let mut group = TabGroup::new(vec![1, 2, 3], 20.0);
+        group.event(RECT, IDENTITY, &cursor([150.0, 10.0]));
+        assert_eq!(group.state(1), SplitState::Hover);
+        group.event(RECT, IDENTITY, &button(ButtonState::Press));
+        group.event(RECT, IDENTITY, &button(ButtonState::Release));
+        assert_eq!(group.active_panel(), Some(2));
+        assert_eq!(group.dragging(), None);
+
+        // Clicks below the header are ignored.
+        group.event(RECT, IDENTITY, &cursor([50.0, 100.0]));
+        group.event(RECT, IDENTITY, &button(ButtonState::Press));
+        assert_eq!(group.active_panel(), Some(2));
+    }
+
+    #[test]
+    fn drag_reorders_tabs() {
+        let mut group = TabGroup::new(vec![1, 2, 3], 20.0);
+        group.event(RECT, IDENTITY, &cursor([50.0, 10.0]));
+        group.event(RECT, IDENTITY, &button(ButtonState::Press));
+        group.event(RECT, IDENTITY, &cursor([150.0, 10.0]));
+        assert_eq!(group.panels, vec![2, 1, 3]);
+        group.event(RECT, IDENTITY, &cursor([250.0, 10.0]));
+        assert_eq!(group.panels, vec![2, 3, 1]);
+        assert_eq!(group.dragging(), Some(2));
+        assert_eq!(group.active_panel(), Some(1));
+        group.event(RECT, IDENTITY, &button(ButtonState::Release));
+        assert_eq!(group.dragging(), None);
+        assert_eq!(group.panels, vec![2, 3, 1]);
+    }
+}
@@ -0,0 +1,375 @@
+//! Recursive split tree layout.
+
+use std::mem;
+
+use input::GenericEvent;
+
+use super::math::{Matrix2d, Rectangle};
+use super::{SplitController, SplitLayout, SplitOrientation, SplitState, TabGroup};
+
+/// Stores a node in a split tree.
+pub enum SplitNode {
+    /// A leaf panel with an id.
+    Leaf(usize),
+    /// A split of two child nodes.
+    ///
+    /// The orientation of the split controller decides whether the children are placed
+    /// side by side (`Left`/`Right`) or above each other (`Top`/`Bottom`),
+    /// and which child is sized by the split value.
+    Split {
+        /// The split controller.
+        split: Box<SplitController>,
+        /// The first child, to the left or above.
+        first: Box<SplitNode>,
+        /// The second child, to the right or below.
+        second: Box<SplitNode>,
+    },
+    /// A group of panels shown as tabs.
+    Tabs(TabGroup),
+}
+
+impl SplitNode {
+    /// Creates a new split node.
+    pub fn split(split: SplitController, first: SplitNode, second: SplitNode) -> SplitNode {
+        SplitNode::Split {
+            split: Box::new(split),
+            first: Box::new(first),
+            second: Box::new(second),
+        }
+    }
+
+    /// Gets whether this node is a leaf panel with an id.
+    pub fn is_leaf(&self, id: usize) -> bool {
+        match *self {
+            SplitNode::Leaf(leaf) => leaf == id,
+            SplitNode::Split {..} | SplitNode::Tabs(_) => false,
+        }
+    }
+
+    /// Gets whether this node is a leaf panel or tab group with a panel.
+    ///
+    /// Does not look into children of a split.
+    pub fn has_panel(&self, id: usize) -> bool {
+        match *self {
+            SplitNode::Leaf(leaf) => leaf == id,
+            SplitNode::Split {..} => false,
+            SplitNode::Tabs(ref group) => group.contains(id),
+        }
+    }
+
+    /// Gets the leaf node or tab group of a panel.
+    pub fn leaf_mut(&mut self, id: usize) -> Option<&mut SplitNode> {
+        if self.has_panel(id) {return Some(self)};
+
+        match *self {
+            SplitNode::Leaf(_) | SplitNode::Tabs(_) => None,
+            SplitNode::Split {ref mut first, ref mut second, ..} => {
+                match first.leaf_mut(id) {
+                    Some(leaf) => Some(leaf),
+                    None => second.leaf_mut(id),
+                }
+            }
+        }
+    }
+
+    /// Removes a panel, replacing its parent split with the other child.
+    ///
+    /// A panel in a tab group is removed from the group,
+    /// and the group becomes a leaf when one panel is left.
+    /// Returns `false` if the panel is not found or is the only panel in this node.
+    pub fn remove(&mut self, id: usize) -> bool {
+        let other = match *self {
+            SplitNode::Leaf(_) => return false,
+            SplitNode::Tabs(ref mut group) => {
+                if group.panels.len() < 2 || !group.remove(id) {return false};
+                if group.panels.len() > 1 {return true};
+                SplitNode::Leaf(group.panels[0])
+            }
+            SplitNode::Split {ref mut first, ref mut second, ..} => {
+                if first.is_only(id) {
+                    mem::replace(&mut **second, SplitNode::Leaf(id))
+                } else if second.is_only(id) {
+                    mem::replace(&mut **first, SplitNode::Leaf(id))
+                } else {
+                    return first.remove(id) || second.remove(id);
+                }
+            }
+        };
+        *self = other;
+        true
+    }
+
+    /// Gets whether this node contains only a panel.
+    fn is_only(&self, id: usize) -> bool {
+        match *self {
+            SplitNode::Tabs(ref group) => group.panels == [id],
+            _ => self.is_leaf(id),
+        }
+    }
+
+    /// Gets whether a split in this node or its children is being dragged.
+    pub fn is_dragging(&self) -> bool {
+        match *self {
+            SplitNode::Leaf(_) | SplitNode::Tabs(_) => false,
+            SplitNode::Split {ref split, ref first, ref second} => {
+                split.is_dragging() || first.is_dragging() || second.is_dragging()
+            }
+        }
+    }
+
+    /// Computes the minimum size using current values in split controls.
+    pub fn min_size(&self) -> [f64; 2] {
+        match *self {
+            SplitNode::Leaf(_) => [0.0; 2],
+            SplitNode::Tabs(ref group) => [0.0, group.header_height],
+            SplitNode::Split {ref split, ref first, ref second} => {
+                let a = first.min_size();
+                let b = second.min_size();
+                let value = split.visible_value();
+                let border = split.visible_border();
+                match split.orientation {
+                    SplitOrientation::Left => [value + border + b[0], a[1].max(b[1])],
+                    SplitOrientation::Right => [a[0] + border + value, a[1].max(b[1])],
+                    SplitOrientation::Top => [a[0].max(b[0]), value + border + b[1]],
+                    SplitOrientation::Bottom => [a[0].max(b[0]), a[1] + border + value],
+                }
+            }
+        }
+    }
+
+    fn event<E: GenericEvent>(
+        &mut self,
+        rect: Rectangle,
+        dragging: bool,
+        transform: Matrix2d,
+        e: &E
+    ) {
+        if let SplitNode::Tabs(ref mut group) = *self {
+            if !dragging {
+                group.event(rect, transform, e);
+            }
+        }
+        if let SplitNode::Split {ref mut split, ref mut first, ref mut second} = *self {
+            if !dragging || split.is_dragging() {
+                let max_value = match split.orientation {
+                    SplitOrientation::Left => rect[2] - split.border - second.min_size()[0],
+                    SplitOrientation::Right => rect[2] - split.border - first.min_size()[0],
+                    SplitOrientation::Top => rect[3] - split.border - second.min_size()[1],
+                    SplitOrientation::Bottom => rect[3] - split.border - first.min_size()[1],
+                };
+                split.event(LAYOUT, max_value, rect, transform, e);
+            }
+            let children = child_rectangles(split, rect);
+            first.event(children[0], dragging, transform, e);
+            second.event(children[1], dragging, transform, e);
+        }
+    }
+
+    fn set_touch_scale(&mut self, touch_scale: [f64; 2]) {
+        if let SplitNode::Split {ref mut split, ref mut first, ref mut second} = *self {
+            split.touch_scale = touch_scale;
+            first.set_touch_scale(touch_scale);
+            second.set_touch_scale(touch_scale);
+        }
+    }
+
+    fn rectangles(&self, rect: Rectangle, rectangles: &mut Vec<Rectangle>) {
+        if let SplitNode::Split {ref split, ref first, ref second} = *self {
+            rectangles.push(split.line_rect(LAYOUT, rect));
+            let children = child_rectangles(split, rect);
+            first.rectangles(children[0], rectangles);
+            second.rectangles(children[1], rectangles);
+        }
+    }
+
+    fn states(&self, states: &mut Vec<SplitState>) {
+        if let SplitNode::Split {ref split, ref first, ref second} = *self {
+            states.push(split.state());
+            first.states(states);
+            second.states(states);
+        }
+    }
+
+    fn panel_rectangles(&self, rect: Rectangle, panels: &mut Vec<(usize, Rectangle)>) {
+        match *self {
+            SplitNode::Leaf(id) => panels.push((id, rect)),
+            SplitNode::Split {ref split, ref first, ref second} => {
+                let children = child_rectangles(split, rect);
+                first.panel_rectangles(children[0], panels);
+                second.panel_rectangles(children[1], panels);
+            }
+            SplitNode::Tabs(ref group) => {
+                if let Some(id) = group.active_panel() {
+                    panels.push((id, group.content_rect(rect)));
+                }
+            }
+        }
+    }
+
+    fn tab_rectangles(&self, rect: Rectangle, tabs: &mut Vec<(usize, Rectangle)>) {
+        match *self {
+            SplitNode::Leaf(_) => {}
+            SplitNode::Split {ref split, ref first, ref second} => {
+                let children = child_rectangles(split, rect);
+                first.tab_rectangles(children[0], tabs);
+                second.tab_rectangles(children[1], tabs);
+            }
+            SplitNode::Tabs(ref group) => {
+                tabs.extend(group.panels.iter().cloned().zip(group.tab_rectangles(rect)));
+            }
+        }
+    }
+}
+
+/// Stores a tree of splits, where each leaf is a panel with an id.
+pub struct SplitTree {
+    /// The root node.
+    pub root: SplitNode,
+}
+
+impl SplitTree {
+    /// Creates a new `SplitTree`.
+    pub fn new(root: SplitNode) -> SplitTree {
+        SplitTree {root}
+    }
+
+    /// Handles event.
+    ///
+    /// While a split is dragged, the other splits do not receive events,
+    /// but all splits track the window size from resize events for touch input.
+    pub fn event<E: GenericEvent>(&mut self, rect: Rectangle, transform: Matrix2d, e: &E) {
+        if let Some(args) = e.resize_args() {
+            self.set_touch_scale(args.window_size);
+        }
+        let bounds = self.bounds(rect);
+        let dragging = self.root.is_dragging();
+        self.root.event(bounds, dragging, transform, e);
+    }
+
+    /// Sets the scale of normalized touch positions for all splits, usually the window size.
+    ///
+    /// This is required for touch input until the first resize event.
+    pub fn set_touch_scale(&mut self, touch_scale: [f64; 2]) {
+        self.root.set_touch_scale(touch_scale);
+    }
+
+    /// Computes split rectangles for drawing, in depth-first order.
+    pub fn rectangles(&self, rect: Rectangle) -> Vec<Rectangle> {
+        let mut rectangles = vec![];
+        self.root.rectangles(self.bounds(rect), &mut rectangles);
+        rectangles
+    }
+
+    /// Returns the split controller states, in depth-first order.
+    pub fn states(&self) -> Vec<SplitState> {
+        let mut states = vec![];
+        self.root.states(&mut states);
+        states
+    }
+
+    /// Computes panel rectangles for layout, keyed by panel id.
+    ///
+    /// Only the active panel of a tab group is included, below the tab header.
+    pub fn panel_rectangles(&self, rect: Rectangle) -> Vec<(usize, Rectangle)> {
+        let mut panels = vec![];
+        self.root.panel_rectangles(self.bounds(rect), &mut panels);
+        panels
+    }
+
+    /// Computes tab rectangles of tab groups for drawing, keyed by panel id.
+    pub fn tab_rectangles(&self, rect: Rectangle) -> Vec<(usize, Rectangle)> {
+        let mut tabs = vec![];
+        self.root.tab_rectangles(self.bounds(rect), &mut tabs);
+        tabs
+    }
+
+    /// Computes the minimum size using current values in split controls.
+    pub fn min_size(&self) -> [f64; 2] {self.root.min_size()}
+
+    /// Computes the bounds from window bounds `[x, y, w, h]`.
+    ///
+    /// Does not get less in size than specified by `min_size`.
+    pub fn bounds(&self, rect: Rectangle) -> Rectangle {
+        let min_size = self.min_size();
+        [rect[0], rect[1], rect[2].max(min_size[0]), rect[3].max(min_size[1])]
+    }
+}
+
+const LAYOUT: SplitLayout = SplitLayout {start: 0.0, end: 0.0};
+
+/// Computes rectangles `[first, second]` of child nodes.
+fn child_rectangles(split: &SplitController, rect: Rectangle) -> [Rectangle; 2] {
+    let value = split.visible_value();
+    let border = split.visible_border();
+    match split.orientation {
+        SplitOrientation::Left => [
+            [rect[0], rect[1], value, rect[3]],
+            [rect[0] + value + border, rect[1], rect[2] - value - border, rect[3]],
+        ],
+        SplitOrientation::Right => [
+            [rect[0], rect[1], rect[2] - value - border, rect[3]],
+            [rect[0] + rect[2] - value, rect[1], value, rect[3]],
+        ],
+        SplitOrientation::Top => [
+            [rect[0], rect[1], rect[2], value],
+            [rect[0], rect[1] + value + border, rect[2], rect[3] - value - border],
+        ],
+        SplitOrientation::Bottom => [
+            [rect[0], rect[1], rect[2], rect[3] - value - border],
+            [rect[0], rect[1] + rect[3] - value, rect[2], value],
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECT: Rectangle = [0.0, 0.0, 400.0, 300.0];
+
+    fn tree() -> SplitTree {
+        let top = SplitController::new(100.0, 10.0, 2.0, SplitOrientation::Top);
+        let left = SplitController::new(100.0, 10.0, 2.0, SplitOrientation::Left);
+        SplitTree::new(SplitNode::split(
+            left,
+            SplitNode::Leaf(1),
+            SplitNode::split(top, SplitNode::Leaf(2), SplitNode::Leaf(3)),
+        ))
+    }
+
+    fn panels(tree: &SplitTree) -> Vec<(usize, Rectangle)> {
+        tree.panel_rectangles(RECT)
+    }
+
+    #[test]
+    fn remove_replaces_parent_split() {
+        let mut tree = tree();
+        assert!(tree.root.remove(2));
+        assert_eq!(panels(&tree), vec![
+            (1, [0.0, 0.0, 100.0, 300.0]),
+            (3, [102.0, 0.0, 298.0, 300.0]),
+        ]);
+        assert!(tree.root.remove(1));
+        assert!(tree.root.is_leaf(3));
+    }
+
+    #[test]
+    fn remove_only_or_missing_panel() {
+        let mut tree = tree();
+        assert!(!tree.root.remove(4));
+        assert!(tree.root.remove(1));
+        assert!(tree.root.remove(2));
+        assert!(!tree.root.remove(3));
+        assert!(tree.root.is_leaf(3));
+    }
+
+    #[test]
+    fn remove_from_tab_group() {
+        let mut tree = tree();
+        *tree.root.leaf_mut(3).unwrap() = SplitNode::Tabs(TabGroup::new(vec![3, 4, 5], 20.0));
+        assert!(tree.root.remove(4));
+        assert!(tree.root.leaf_mut(3).unwrap().has_panel(5));
+        assert!(tree.root.remove(3));
+        assert!(tree.root.leaf_mut(5).unwrap().is_leaf(5));
+    }
+}